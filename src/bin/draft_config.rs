@@ -0,0 +1,95 @@
+//! Draft a config from a saved manga page
+//!
+//! ```text
+//! draft_config <manga page.html> --url <url> --title <title> [--chapter <chapter title>]
+//!     [--chapter-page <chapter page.html>] [--image <image url>]
+//! ```
+//!
+//! The YAML is written to stdout, notes and schema problems to stderr
+
+use std::{fs, process::ExitCode};
+
+use manga_parser::{
+    scraper::infer::{draft_config, SamplePage},
+    Url,
+};
+
+const USAGE: &str = "usage: draft_config <manga page.html> --url <url> --title <title> [--chapter <chapter title>] \
+    [--chapter-page <chapter page.html>] [--image <image url>]";
+
+#[derive(Default)]
+struct Args {
+    page: Option<String>,
+    url: Option<String>,
+    title: Option<String>,
+    chapter: Option<String>,
+    chapter_page: Option<String>,
+    image: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut input = std::env::args().skip(1);
+    while let Some(arg) = input.next() {
+        let field = match arg.as_str() {
+            "--url" => &mut args.url,
+            "--title" => &mut args.title,
+            "--chapter" => &mut args.chapter,
+            "--chapter-page" => &mut args.chapter_page,
+            "--image" => &mut args.image,
+            "-h" | "--help" => return Err(String::from(USAGE)),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {flag}\n{USAGE}")),
+            _ => &mut args.page,
+        };
+        *field = Some(if arg.starts_with("--") {
+            input.next().ok_or_else(|| format!("{arg} needs a value\n{USAGE}"))?
+        } else {
+            arg
+        });
+    }
+    Ok(args)
+}
+
+fn run() -> Result<(), String> {
+    let args = parse_args()?;
+    let (Some(page), Some(url), Some(title)) = (args.page, args.url, args.title) else {
+        return Err(String::from(USAGE));
+    };
+    let url = Url::parse(&url).map_err(|e| format!("{url} is not a URL: {e}"))?;
+    let html = fs::read_to_string(&page).map_err(|e| format!("could not read {page}: {e}"))?;
+    let chapter_html = args
+        .chapter_page
+        .map(|chapter_page| {
+            fs::read_to_string(&chapter_page).map_err(|e| format!("could not read {chapter_page}: {e}"))
+        })
+        .transpose()?;
+
+    let draft = draft_config(&SamplePage {
+        url: &url,
+        html: &html,
+        title: &title,
+        chapter_title: args.chapter.as_deref(),
+        chapter_html: chapter_html.as_deref(),
+        image_url: args.image.as_deref(),
+    })
+    .map_err(|e| e.to_string())?;
+
+    print!("{}", draft.to_yaml());
+    for note in &draft.notes {
+        eprintln!("note: {note}");
+    }
+    for issue in draft.issues() {
+        eprintln!("{issue}");
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,4 +1,9 @@
+use std::fmt::Display;
+
 use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::schema::{ConfigSchema, SchemaGenerator};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
@@ -7,5 +12,56 @@ pub struct Accept {
     pub selectors: Vec<String>,
     #[serde(default)]
     pub hostnames: Vec<String>,
+    /// Themes the config is written for, it is tried on pages fingerprinted as one of them
+    #[serde(default)]
+    pub themes: Vec<Theme>,
+}
+
+/// A CMS theme that many websites share, recognised by [`crate::scraper::fingerprint`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// The WordPress Madara theme
+    Madara,
+    /// The WordPress MangaStream and MangaReader themes
+    MangaStream,
+    /// FoOlSlide reader
+    FoolSlide,
 }
 
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Madara, Theme::MangaStream, Theme::FoolSlide];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Madara => "madara",
+            Theme::MangaStream => "mangastream",
+            Theme::FoolSlide => "foolslide",
+        }
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl ConfigSchema for Accept {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "selectors": generator.described::<Vec<String>>("A page is accepted when one of these selectors matches"),
+                "hostnames": generator.described::<Vec<String>>("A URL is accepted when its hostname is one of these"),
+                "themes": {
+                    "type": "array",
+                    "items": { "enum": Theme::ALL.map(|theme| theme.name()) },
+                    "description": "A page is accepted when it is fingerprinted as one of these themes, after the configs accepting it by hostname or selector",
+                },
+            },
+        })
+    }
+}
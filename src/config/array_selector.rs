@@ -1,6 +1,10 @@
 use serde::Deserialize;
+use serde_json::{json, Value};
 
-use super::array_selector_options::ArraySelectorOptions;
+use super::{
+    schema::{self, ConfigSchema, Location, SchemaGenerator},
+    array_selector_options::ArraySelectorOptions,
+};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct ArraySelector {
@@ -31,7 +35,7 @@ impl<'de> Deserialize<'de> for ArraySelectors {
                 where
                     A: serde::de::SeqAccess<'de>, {
                 let mut selectors = vec![];
-                while let Ok(Some(element)) = seq.next_element() {
+                while let Some(element) = seq.next_element()? {
                     selectors.push(element);
                 }
 
@@ -74,7 +78,7 @@ impl<'de> Deserialize<'de> for ArraySelectors {
                             options = Some(map.next_value()?);
                         }
                         _ => {
-                            // Ignore unknown fields
+                            // Unknown fields are reported as warnings when the config is validated
                             let _ = map.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
@@ -152,7 +156,7 @@ impl<'de> Deserialize<'de> for ArraySelector {
                             options = Some(map.next_value()?);
                         }
                         _ => {
-                            // Ignore unknown fields
+                            // Unknown fields are reported as warnings when the config is validated
                             let _ = map.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
@@ -172,6 +176,51 @@ impl<'de> Deserialize<'de> for ArraySelector {
     }
 }
 
+impl ConfigSchema for ArraySelectors {
+    const LOCATION: Option<Location> = Some(Location::root(schema::ARRAY_SELECTOR_SCHEMA));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "title": "Array Selector",
+            "description": "Select a list of elements and extract their texts in an array",
+            "oneOf": [
+                {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "anyOf": [
+                            generator.subschema::<ArraySelector>(),
+                            selector_string(generator),
+                        ],
+                    },
+                },
+                generator.subschema::<ArraySelector>(),
+                selector_string(generator),
+            ],
+        })
+    }
+}
+
+impl ConfigSchema for ArraySelector {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::ARRAY_SELECTOR_SCHEMA, "selector"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "required": ["selector"],
+            "properties": {
+                "selector": selector_string(generator),
+                "options": generator.subschema::<ArraySelectorOptions>(),
+            },
+        })
+    }
+}
+
+/// The plain string form of a selector
+fn selector_string(generator: &SchemaGenerator) -> Value {
+    generator.described::<String>("Query selector, or a JSON path like $.data.chapters[*] when the response is JSON")
+}
+
 #[cfg(test)]
 mod test {
     use std::path::Path;
@@ -1,7 +1,12 @@
 use regex::Regex;
 use serde::Deserialize;
+use serde_json::{json, Value};
 
-use super::string_selector_options::{StringSelection, FixCapitalization, CleanupOption};
+use super::{
+    schema::{ConfigSchema, SchemaGenerator},
+    string_selector_options::{CleanupOption, FixCapitalization, ScriptExtraction, StringSelection},
+    transform::Transform,
+};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
@@ -15,6 +20,12 @@ pub struct ArraySelectorOptions {
     /// Determine how text should be selected
     #[serde(default)]
     pub text_selection: StringSelection,
+    /// Parse values out of the selected script
+    #[serde(default)]
+    pub script: Option<ScriptExtraction>,
+    /// Steps applied in order to the selected text, before it is trimmed and cleaned up
+    #[serde(default)]
+    pub transforms: Vec<Transform>,
     /// Determine how text should splitted
     /// When one element is selected,
     /// should a piece of text be splitted to result in an array of Strings
@@ -33,7 +44,32 @@ impl Default for ArraySelectorOptions {
             cleanup: vec![],
             text_selection: StringSelection::default(),
             fix_capitalization: FixCapitalization::default(),
+            script: None,
+            transforms: vec![],
             text_split_regex: default_text_split_regex(),
         }
     }
 }
+
+impl ConfigSchema for ArraySelectorOptions {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "cleanup": generator.described::<Vec<CleanupOption>>("Cleanup text that has been scraped with regexps"),
+                "fix_capitalization": generator.subschema::<FixCapitalization>(),
+                "text_selection": generator.subschema::<StringSelection>(),
+                "script": generator.subschema::<Option<ScriptExtraction>>(),
+                "transforms": generator.described::<Vec<Transform>>(
+                    "Steps applied in order to the selected text, before it is trimmed and cleaned up"
+                ),
+                "text_split_regex": {
+                    "type": ["string", "null"],
+                    "format": "regex",
+                    "description": "Split the text of a single selected element into an array, null keeps the text whole",
+                    "default": default_text_split_regex().map(|regex| regex.as_str().to_string()),
+                },
+            },
+        })
+    }
+}
@@ -1,7 +1,21 @@
+use std::collections::HashMap;
+
 use regex::Regex;
+use reqwest::Url;
 use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    error::ScrapeError,
+    util::number::{self, ChapterNumber},
+};
 
-use super::string_selector::StringSelectors;
+use super::{
+    request::{self, RequestBody},
+    schema::{self, ConfigSchema, Location, SchemaGenerator},
+    string_selector::StringSelectors,
+    string_selector_options::StringSelection,
+};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
@@ -10,11 +24,20 @@ pub struct Chapter {
     pub title: StringSelectors,
     #[serde(default)]
     pub number: Option<StringSelectors>,
+    /// Chapter number in the number text, or the title when there is no number selector
+    #[serde(default, deserialize_with = "serde_regex::deserialize")]
+    pub number_regex: Option<Regex>,
+    /// Volume in the number text or the title
+    #[serde(default, deserialize_with = "serde_regex::deserialize")]
+    pub volume_regex: Option<Regex>,
     #[serde(default)]
     pub date: Option<StringSelectors>,
     pub url: StringSelectors,
     #[serde(default)]
     pub fetch_external: Vec<FetchExternal>,
+    /// Follow the pages of a paginated chapter list
+    #[serde(default)]
+    pub pagination: Option<Pagination>,
 }
 
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -23,11 +46,235 @@ pub struct FetchExternal {
     pub id: StringSelectors,
     #[serde(deserialize_with = "serde_regex::deserialize")]
     pub regex: Regex,
+    /// URL of the request, `{id}` is the id found with the regex, see [`super::request::variables`]
     pub url: String,
     #[serde(default = "default_method")]
     pub method: String,
+    /// Headers sent with the request, filled in like `url`
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Body sent with the request, filled in like `url`
+    #[serde(default)]
+    pub body: Option<RequestBody>,
 }
 
-fn default_method() -> String {
+pub(crate) fn default_method() -> String {
     return String::from("get");
-}
\ No newline at end of file
+}
+
+/// How to get from one page of a list to the next
+///
+/// Either `next_page` selects the link to the next page, or `url` is a template where `{page}` is the page number.
+/// Pages are fetched until one has nothing new, `stop_selector` matches, or `max_pages` is reached
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize)]
+pub struct Pagination {
+    /// Link to the next page
+    #[serde(default)]
+    pub next_page: Option<StringSelectors>,
+    /// URL of a page, `{page}`, `{host}` and `{url}` are replaced with the page number, hostname and URL of the first page
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Number of the page after the first one, used with `url`
+    #[serde(default = "default_start_page")]
+    pub start_page: u32,
+    /// Stop after a page this selector matches on
+    #[serde(default)]
+    pub stop_selector: Option<String>,
+    /// Maximum number of pages, including the first one
+    #[serde(default = "default_max_pages")]
+    pub max_pages: u32,
+}
+
+fn default_start_page() -> u32 {
+    2
+}
+
+fn default_max_pages() -> u32 {
+    20
+}
+
+impl Chapter {
+    /// Number and volume of a chapter, the regexes of the config go before the common patterns (see [`number::parse_chapter_number`])
+    pub fn number_and_volume(&self, number_text: &str, title: &str) -> (ChapterNumber, Option<f32>) {
+        let (parsed_number, parsed_volume) = number::parse_chapter_number(number_text);
+
+        let number = self
+            .number_regex
+            .as_ref()
+            .and_then(|regex| number::try_parse_number(&StringSelection::capture(regex, number_text)))
+            .map_or(parsed_number, ChapterNumber::Number);
+        let volume = match &self.volume_regex {
+            Some(regex) => [number_text, title]
+                .into_iter()
+                .find_map(|text| number::try_parse_number(&StringSelection::capture(regex, text))),
+            None => parsed_volume.or_else(|| number::parse_chapter_number(title).1),
+        };
+        (number, volume)
+    }
+}
+
+impl Pagination {
+    /// URL of the `index`th page after the first one from the `url` template
+    pub fn page_url(&self, url: &Url, index: u32) -> Option<Result<Url, ScrapeError>> {
+        let template = self.url.as_ref()?;
+        let page = (self.start_page + index).to_string();
+        let page_url = request::interpolate(
+            template,
+            &[("page", &page), ("host", url.host_str().unwrap_or_default()), ("url", url.as_str())],
+        );
+        Some(url.join(&page_url).map_err(|e| ScrapeError::NotAValidURL(e.to_string())))
+    }
+}
+
+impl ConfigSchema for Chapter {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "chapter"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "required": ["base", "title", "url"],
+            "properties": {
+                "base": generator.subschema::<StringSelectors>(),
+                "title": generator.subschema::<StringSelectors>(),
+                "number": generator.subschema::<Option<StringSelectors>>(),
+                "number_regex": generator.described::<Regex>(
+                    "Chapter number in the number text, or the title when there is no number selector. \
+                    Uses the group called value, the first group or the whole match. \
+                    Without it numbers like Vol.2 Chapter 15.5, Ch. 10-11, Prologue and Side Story 3 are understood"
+                ),
+                "volume_regex": generator.described::<Regex>(
+                    "Volume in the number text or the title, uses the group called value, the first group or the whole match"
+                ),
+                "date": generator.subschema::<Option<StringSelectors>>(),
+                "url": generator.subschema::<StringSelectors>(),
+                "fetch_external": generator.subschema::<Vec<FetchExternal>>(),
+                "pagination": generator.subschema::<Option<Pagination>>(),
+            },
+        })
+    }
+}
+
+impl ConfigSchema for FetchExternal {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "fetch_external"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "regex", "url"],
+            "properties": {
+                "id": generator.subschema::<StringSelectors>(),
+                "regex": generator.described::<Regex>("Regex should return a group called id which is used in the url"),
+                "url": generator.described::<String>(
+                    "URL where chapters are hosted. {id} is the id found with the regex, {host} (or {hostname}) and {url} are from the page and {query} is empty"
+                ),
+                "method": method_schema(),
+                "headers": generator.described::<HashMap<String, String>>(
+                    "Headers sent with the request, filled in like url"
+                ),
+                "body": generator.described::<Option<RequestBody>>(
+                    "Body sent with the request, filled in like url"
+                ),
+            },
+        })
+    }
+}
+
+/// Schema of the HTTP method of a request
+pub(crate) fn method_schema() -> Value {
+    json!({
+        "default": default_method(),
+        "enum": ["get", "post"],
+    })
+}
+
+impl ConfigSchema for Pagination {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "pagination"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        let mut url = generator.described::<String>(
+            "URL of a page, {page}, {host} and {url} are replaced with the page number, hostname and URL of the first page",
+        );
+        url["examples"] = json!(["{url}?page={page}", "/ajax/chapters?page={page}"]);
+
+        json!({
+            "description": "Follow the pages of a list, with either a link to the next page or a URL template. \
+                Pages are fetched until one has nothing new, stop_selector matches or max_pages is reached",
+            "type": "object",
+            "anyOf": [
+                { "required": ["next_page"] },
+                { "required": ["url"] },
+            ],
+            "properties": {
+                "next_page": generator.described::<StringSelectors>("Link to the next page"),
+                "url": url,
+                "start_page": {
+                    "type": "integer",
+                    "description": "Number of the page after the first one, used with url",
+                    "default": default_start_page(),
+                },
+                "stop_selector": generator.described::<String>("Stop after a page this selector matches on"),
+                "max_pages": {
+                    "type": "integer",
+                    "description": "Maximum number of pages, including the first one",
+                    "default": default_max_pages(),
+                },
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::{builder::DefaultState, ConfigBuilder, File, FileFormat};
+    use reqwest::Url;
+
+    use super::{Chapter, Pagination};
+    use crate::util::number::ChapterNumber;
+
+    #[test]
+    fn test_number_and_volume() {
+        let chapter = ConfigBuilder::<DefaultState>::default()
+            .add_source(File::from_str(
+                "base: li\ntitle: a\nurl: a\nnumber_regex: 'Ep(\\d+)'\nvolume_regex: 'S(?<value>\\d+)'\n",
+                FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize::<Chapter>()
+            .unwrap();
+        assert_eq!(
+            chapter.number_and_volume("S2Ep15", "Season 2"),
+            (ChapterNumber::Number(15.0), Some(2.0))
+        );
+        assert_eq!(
+            chapter.number_and_volume("Side Story 3", "Side Story 3"),
+            (ChapterNumber::Special(Some(3)), None)
+        );
+    }
+
+    #[test]
+    fn test_page_url() {
+        let pagination = Pagination {
+            next_page: None,
+            url: Some(String::from("{url}?page={page}")),
+            start_page: 2,
+            stop_selector: None,
+            max_pages: 5,
+        };
+        let url = Url::parse("https://example.com/manga/solo-leveling").unwrap();
+        assert_eq!(
+            pagination.page_url(&url, 0).unwrap().unwrap().as_str(),
+            "https://example.com/manga/solo-leveling?page=2"
+        );
+
+        let pagination = Pagination {
+            url: Some(String::from("/ajax/chapters/{page}")),
+            ..pagination
+        };
+        assert_eq!(
+            pagination.page_url(&url, 3).unwrap().unwrap().as_str(),
+            "https://example.com/ajax/chapters/5"
+        );
+    }
+}
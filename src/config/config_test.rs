@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::schema::{self, ConfigSchema, Location, SchemaGenerator};
+
+/// An offline test of a config, every page the scraper fetches is read from a fixture file instead
+///
+/// Run by [`crate::scraper::generic::GenericScraper::run_config_tests`]
+///
+/// ```yaml
+/// tests:
+///   - url: https://isekaiscan.top/manga/god-of-martial-arts/
+///     fixture: tests/fragments/madara.html
+///     pages:
+///       - url: /ajax-list-chapter?mangaID=25960
+///         fixture: tests/fragments/madara_chapters.html
+///     expect:
+///       title: God Of Martial Arts
+///       chapters: 3
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize)]
+pub struct ConfigTest {
+    /// URL of the manga page
+    pub url: String,
+    /// File with the manga page, relative to the working directory
+    pub fixture: String,
+    /// The other pages the scraper fetches, like chapter lists and the first chapter for `images`
+    #[serde(default)]
+    pub pages: Vec<TestPage>,
+    pub expect: TestExpectation,
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize)]
+pub struct TestPage {
+    /// URL of the page, relative to the manga page
+    pub url: String,
+    /// File with the page, relative to the working directory
+    pub fixture: String,
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize)]
+pub struct TestExpectation {
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Number of chapters
+    #[serde(default)]
+    pub chapters: Option<usize>,
+    /// The first chapter in the list
+    #[serde(default)]
+    pub first_chapter: Option<ExpectedChapter>,
+    /// Number of images of the first chapter in the list
+    #[serde(default)]
+    pub images: Option<usize>,
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize)]
+pub struct ExpectedChapter {
+    #[serde(default)]
+    pub number: Option<f32>,
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+}
+
+impl ConfigSchema for ConfigTest {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "test"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "description": "An offline test of the config, every page the scraper fetches is read from a fixture file instead",
+            "type": "object",
+            "required": ["url", "fixture", "expect"],
+            "properties": {
+                "url": generator.described::<String>("URL of the manga page"),
+                "fixture": generator.described::<String>("File with the manga page, relative to the working directory"),
+                "pages": {
+                    "description": "The other pages the scraper fetches, like chapter lists and the first chapter for images",
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["url", "fixture"],
+                        "properties": {
+                            "url": generator.described::<String>("URL of the page, relative to the manga page"),
+                            "fixture": generator.described::<String>(
+                                "File with the page, relative to the working directory"
+                            ),
+                        },
+                    },
+                },
+                "expect": {
+                    "type": "object",
+                    "properties": {
+                        "title": generator.subschema::<String>(),
+                        "chapters": {
+                            "description": "Number of chapters",
+                            "type": "integer",
+                        },
+                        "first_chapter": {
+                            "description": "The first chapter in the list",
+                            "type": "object",
+                            "properties": {
+                                "number": { "type": "number" },
+                                "date": { "type": "string", "format": "date" },
+                            },
+                        },
+                        "images": {
+                            "description": "Number of images of the first chapter in the list",
+                            "type": "integer",
+                        },
+                    },
+                },
+            },
+        })
+    }
+}
@@ -0,0 +1,238 @@
+use std::{collections::HashMap, time::Duration};
+
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::schema::{self, ConfigSchema, Location, SchemaGenerator};
+
+/// HTTP settings used for every request made on behalf of a config
+///
+/// ```yaml
+/// http:
+///   headers:
+///     X-Requested-With: XMLHttpRequest
+///   cookies:
+///     adult: "1"
+///   referer: page
+///   timeout: 10
+///   rate_limit:
+///     requests_per_second: 1
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, Clone, PartialEq)]
+pub struct HttpConfig {
+    /// Headers sent with every request, request specific headers replace them
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Cookies set for the host of every request
+    #[serde(default)]
+    pub cookies: HashMap<String, String>,
+    /// What to send as `Referer`, `Origin` is the origin of the referer
+    #[serde(default)]
+    pub referer: RefererPolicy,
+    /// Seconds to wait for a response
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+    #[serde(default = "default_follow_redirects")]
+    pub follow_redirects: bool,
+    /// Limits on the requests to the hosts of this config
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            headers: HashMap::new(),
+            cookies: HashMap::new(),
+            referer: RefererPolicy::default(),
+            timeout: default_timeout(),
+            follow_redirects: default_follow_redirects(),
+            rate_limit: RateLimit::default(),
+        }
+    }
+}
+
+fn default_timeout() -> u64 {
+    5
+}
+
+fn default_follow_redirects() -> bool {
+    true
+}
+
+/// Limits on the requests to one host, shared by every scraper requesting from it (see [`crate::rate_limit`])
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, Clone, PartialEq)]
+pub struct RateLimit {
+    /// Requests per second once the burst is used up, 0 for no limit
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: f64,
+    /// Requests that can be made right away after a quiet period
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+    /// Requests waiting for a response at the same time
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_second: default_requests_per_second(),
+            burst: default_burst(),
+            max_in_flight: default_max_in_flight(),
+        }
+    }
+}
+
+fn default_requests_per_second() -> f64 {
+    2.0
+}
+
+fn default_burst() -> u32 {
+    5
+}
+
+fn default_max_in_flight() -> u32 {
+    4
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RefererPolicy {
+    /// The requested URL itself
+    #[default]
+    Url,
+    /// The page the request was made from, like the manga page for its chapter list, or the origin when there is none
+    Page,
+    /// The origin of the requested URL
+    Origin,
+    /// No `Referer` or `Origin`
+    None,
+}
+
+impl HttpConfig {
+    /// The `Referer` of a request to `url`, made from the `page` if there is one
+    pub fn referer(&self, url: &Url, page: Option<&Url>) -> Option<Url> {
+        match self.referer {
+            RefererPolicy::Url => Some(url.clone()),
+            RefererPolicy::Page => page.cloned().or_else(|| origin(url)),
+            RefererPolicy::Origin => origin(url),
+            RefererPolicy::None => None,
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+
+    /// The cookies as `name=value` pairs in name order
+    pub fn cookie_pairs(&self) -> Vec<String> {
+        let mut cookies = self
+            .cookies
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>();
+        cookies.sort();
+        cookies
+    }
+}
+
+fn origin(url: &Url) -> Option<Url> {
+    Url::parse(&url.origin().ascii_serialization()).ok()
+}
+
+impl ConfigSchema for HttpConfig {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "http"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "description": "HTTP settings used for every request made with this config",
+            "properties": {
+                "headers": generator.described::<HashMap<String, String>>(
+                    "Headers sent with every request, request specific headers replace them"
+                ),
+                "cookies": generator.described::<HashMap<String, String>>("Cookies set for the host of every request"),
+                "referer": {
+                    "description": "What to send as Referer: the requested url, the page the request was made from, \
+                        the origin of the requested URL or none. Origin is the origin of the referer",
+                    "default": "url",
+                    "enum": ["url", "page", "origin", "none"],
+                },
+                "timeout": {
+                    "description": "Seconds to wait for a response",
+                    "type": "integer",
+                    "default": default_timeout(),
+                },
+                "follow_redirects": {
+                    "type": "boolean",
+                    "default": default_follow_redirects(),
+                },
+                "rate_limit": generator.subschema::<RateLimit>(),
+            },
+        })
+    }
+}
+
+impl ConfigSchema for RateLimit {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "rate_limit"));
+
+    fn schema(_generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "description": "Limits on the requests to the hosts of this config, shared with every other config requesting from them",
+            "properties": {
+                "requests_per_second": {
+                    "description": "Requests per second once the burst is used up, 0 for no limit",
+                    "type": "number",
+                    "default": default_requests_per_second(),
+                },
+                "burst": {
+                    "description": "Requests that can be made right away after a quiet period",
+                    "type": "integer",
+                    "default": default_burst(),
+                },
+                "max_in_flight": {
+                    "description": "Requests waiting for a response at the same time",
+                    "type": "integer",
+                    "default": default_max_in_flight(),
+                },
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::Url;
+
+    use super::{HttpConfig, RefererPolicy};
+
+    #[test]
+    fn test_referer() {
+        let url = Url::parse("https://example.com/manga/solo-leveling/chapters?page=2").unwrap();
+        let page = Url::parse("https://example.com/manga/solo-leveling").unwrap();
+        let referer = |referer| {
+            HttpConfig {
+                referer,
+                ..HttpConfig::default()
+            }
+            .referer(&url, Some(&page))
+            .map(String::from)
+        };
+        assert_eq!(referer(RefererPolicy::Url).as_deref(), Some(url.as_str()));
+        assert_eq!(referer(RefererPolicy::Page).as_deref(), Some(page.as_str()));
+        assert_eq!(referer(RefererPolicy::Origin).as_deref(), Some("https://example.com/"));
+        assert_eq!(referer(RefererPolicy::None), None);
+
+        let http = HttpConfig {
+            referer: RefererPolicy::Page,
+            ..HttpConfig::default()
+        };
+        assert_eq!(http.referer(&url, None).map(String::from).as_deref(), Some("https://example.com/"));
+    }
+}
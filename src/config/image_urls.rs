@@ -0,0 +1,137 @@
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{
+    schema::{self, ConfigSchema, Location, SchemaGenerator},
+    string_selector_options::CleanupOption,
+};
+
+/// Rewrites the URLs of chapter images and covers
+///
+/// ```yaml
+/// image_urls:
+///   rewrite:
+///     # Full resolution instead of a thumbnail
+///     - replace_regex: '[?&]w=\d+$'
+///       replace_with: ""
+///   mirrors:
+///     - cdn1.example.com
+///     - cdn2.example.com
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, Default)]
+pub struct ImageUrls {
+    /// Regex replacements applied in turn to the whole URL
+    #[serde(default)]
+    pub rewrite: Vec<CleanupOption>,
+    /// Hosts serving the same images, tried in turn when the host of the chapter images or the manga cover does not
+    /// respond. The covers of search results are only rewritten, checking them would be a request per result
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+}
+
+impl ImageUrls {
+    /// The URL after every rewrite rule, or the URL itself when the rules turn it into something that is not a URL
+    pub fn rewrite(&self, url: Url) -> Url {
+        if self.rewrite.is_empty() {
+            return url;
+        }
+        let rewritten = self.rewrite.iter().fold(url.to_string(), |rewritten, rule| {
+            rule.replace_regex
+                .replace_all(&rewritten, rule.replace_with.as_str())
+                .into_owned()
+        });
+        match Url::parse(&rewritten) {
+            Ok(rewritten) => rewritten,
+            Err(e) => {
+                warn!("[images] rewriting {url} gave {rewritten:?}, which is not a URL: {e}");
+                url
+            }
+        }
+    }
+
+    /// The URL and then the URL on every mirror, in the order they are tried
+    pub fn candidates(&self, url: &Url) -> Vec<Url> {
+        let mut candidates = vec![url.clone()];
+        for mirror in &self.mirrors {
+            if let Some(candidate) = on_host(url, mirror).filter(|candidate| !candidates.contains(candidate)) {
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+/// The URL with another host
+pub fn on_host(url: &Url, host: &str) -> Option<Url> {
+    let mut url = url.clone();
+    url.set_host(Some(host)).ok()?;
+    Some(url)
+}
+
+impl ConfigSchema for ImageUrls {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "image_urls"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "description": "Rewrites the URLs of chapter images and covers",
+            "type": "object",
+            "properties": {
+                "rewrite": generator.described::<Vec<CleanupOption>>(
+                    "Regex replacements applied in turn to the whole URL"
+                ),
+                "mirrors": {
+                    "description": "Hosts serving the same images, tried in turn when the host of the chapter images \
+                        or the manga cover does not respond. The covers of search results are only rewritten",
+                    "type": "array",
+                    "items": generator.subschema::<String>(),
+                    "examples": [["cdn1.example.com", "cdn2.example.com"]],
+                },
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::{builder::DefaultState, ConfigBuilder, File, FileFormat};
+    use reqwest::Url;
+
+    use super::ImageUrls;
+
+    #[test]
+    fn test_image_urls() {
+        let image_urls = ConfigBuilder::<DefaultState>::default()
+            .add_source(File::from_str(
+                r#"
+rewrite:
+  - replace_regex: '[?&]w=\d+$'
+    replace_with: ""
+  - replace_regex: ^https://cdn\d+\.
+    replace_with: https://cdn.
+mirrors: [cdn.example.com, backup.example.com]
+"#,
+                FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize::<ImageUrls>()
+            .unwrap();
+
+        let url = Url::parse("https://cdn3.example.com/manga/1/01.jpg?w=300").unwrap();
+        let rewritten = image_urls.rewrite(url);
+        assert_eq!(rewritten.as_str(), "https://cdn.example.com/manga/1/01.jpg");
+        assert_eq!(
+            image_urls
+                .candidates(&rewritten)
+                .iter()
+                .map(Url::as_str)
+                .collect::<Vec<_>>(),
+            vec![
+                "https://cdn.example.com/manga/1/01.jpg",
+                "https://backup.example.com/manga/1/01.jpg"
+            ]
+        );
+    }
+}
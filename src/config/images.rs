@@ -1,6 +1,11 @@
 use serde::Deserialize;
+use serde_json::{json, Value};
 
-use super::{array_selector::ArraySelectors, chapter::FetchExternal};
+use super::{
+    array_selector::ArraySelectors,
+    chapter::FetchExternal,
+    schema::{ConfigSchema, SchemaGenerator},
+};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
@@ -9,3 +14,16 @@ pub struct Images {
     #[serde(default)]
     pub fetch_external: Vec<FetchExternal>,
 }
+
+impl ConfigSchema for Images {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "required": ["image_selector"],
+            "properties": {
+                "image_selector": generator.subschema::<ArraySelectors>(),
+                "fetch_external": generator.subschema::<Vec<FetchExternal>>(),
+            },
+        })
+    }
+}
@@ -0,0 +1,375 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use config::{builder::DefaultState, ConfigBuilder, File, FileFormat, Map, Value, ValueKind};
+use serde::Deserialize;
+
+use crate::error::ScrapeError;
+
+use super::{
+    migrate,
+    validate::{self, ConfigIssue},
+    MangaScraperConfig,
+};
+
+type Table = Map<String, Value>;
+/// A config file and what it loaded to
+pub type LoadedConfig = (PathBuf, Result<MangaScraperConfig, ScrapeError>);
+type RawConfig = (PathBuf, Result<Table, ScrapeError>);
+/// A config file, what it loaded to and the warnings it got
+type CheckedConfig = (PathBuf, Result<MangaScraperConfig, ScrapeError>, Vec<ConfigIssue>);
+
+/// Check if a file looks like a scraper config
+pub fn is_config_file(path: &Path) -> bool {
+    path.is_file() && config_format(path).is_some()
+}
+
+/// Format of a config file by its extension, every format means the same config
+pub fn config_format(path: &Path) -> Option<FileFormat> {
+    match path.extension().unwrap_or_default().to_str().unwrap_or_default() {
+        "yaml" | "yml" => Some(FileFormat::Yaml),
+        "toml" => Some(FileFormat::Toml),
+        "json" => Some(FileFormat::Json),
+        _ => None,
+    }
+}
+
+/// Where configs come from
+///
+/// When several sources have a config with the same `name`, the one from the last source wins
+#[derive(Clone, Debug)]
+pub enum ConfigSource {
+    /// Every config file in a directory
+    Dir(PathBuf),
+    File(PathBuf),
+    /// Config text in the format of the extension of `name` (YAML without one), `name` stands in for the file in errors
+    Text { name: PathBuf, text: Cow<'static, str> },
+}
+
+/// Configs compiled into the crate
+const BUILTIN_CONFIGS: [(&str, &str); 3] = [
+    ("madara.yaml", include_str!("../../configs/madara.yaml")),
+    ("mangakakalot.yaml", include_str!("../../configs/mangakakalot.yaml")),
+    ("weebcentral.yaml", include_str!("../../configs/weebcentral.yaml")),
+];
+
+impl ConfigSource {
+    /// The configs compiled into the crate, the same as the `configs` directory of the repository
+    pub fn builtin() -> Vec<ConfigSource> {
+        BUILTIN_CONFIGS
+            .into_iter()
+            .map(|(file, text)| ConfigSource::Text {
+                name: Path::new("builtin").join(file),
+                text: Cow::Borrowed(text),
+            })
+            .collect()
+    }
+
+    /// Raw config of every file of the source, migrated to the current version
+    fn read(&self) -> Result<Vec<RawConfig>, ScrapeError> {
+        let raw_configs: Vec<RawConfig> = match self {
+            ConfigSource::Dir(path) => config_files(path)?
+                .into_iter()
+                .map(|file| {
+                    let raw = read_raw_config(&file);
+                    (file, raw)
+                })
+                .collect(),
+            ConfigSource::File(file) => vec![(file.clone(), read_raw_config(file))],
+            ConfigSource::Text { name, text } => {
+                let format = config_format(name).unwrap_or(FileFormat::Yaml);
+                vec![(name.clone(), parse_raw_config(text, format))]
+            }
+        };
+        Ok(raw_configs
+            .into_iter()
+            .map(|(file, raw)| (file, raw.and_then(migrate::migrate)))
+            .collect())
+    }
+}
+
+/// Load every config in a directory, in the order they are tried (see [`MangaScraperConfig::cmp_priority`])
+///
+/// Fails with [`ScrapeError::InvalidConfig`] listing the problems of all configs when any of them is invalid
+pub fn load_configs(path: &Path) -> Result<Vec<MangaScraperConfig>, ScrapeError> {
+    load_sources(&[ConfigSource::Dir(path.to_path_buf())])
+}
+
+/// Load the configs of several sources, like [`load_configs`] does for a directory
+///
+/// Warnings are logged
+pub fn load_sources(sources: &[ConfigSource]) -> Result<Vec<MangaScraperConfig>, ScrapeError> {
+    let (configs, issues) = check_sources(sources)?;
+    let (errors, warnings): (Vec<_>, Vec<_>) = issues.into_iter().partition(ConfigIssue::is_error);
+    for warning in warnings {
+        warn!("[config] {warning}");
+    }
+
+    if errors.is_empty() {
+        Ok(configs)
+    } else {
+        Err(ScrapeError::InvalidConfig(errors))
+    }
+}
+
+/// The configs of several sources that load, in the order they are tried, and the problems of all of them,
+/// warnings included
+pub fn check_sources(sources: &[ConfigSource]) -> Result<(Vec<MangaScraperConfig>, Vec<ConfigIssue>), ScrapeError> {
+    let mut configs = vec![];
+    let mut issues = vec![];
+    for (file, config, warnings) in check_config_sources(sources)? {
+        issues.extend(warnings);
+        match config {
+            Ok(config) => configs.push((file, config)),
+            Err(ScrapeError::InvalidConfig(config_issues)) => issues.extend(config_issues),
+            Err(error) => issues.push(ConfigIssue::new(&file, "", error.to_string())),
+        }
+    }
+    issues.extend(validate::duplicate_hostnames(&configs));
+
+    configs.sort_by(|(_, a), (_, b)| a.cmp_priority(b));
+    Ok((configs.into_iter().map(|(_, config)| config).collect(), issues))
+}
+
+/// Config files in a directory, sorted by path so the load order does not depend on the filesystem
+pub fn config_files(path: &Path) -> Result<Vec<PathBuf>, ScrapeError> {
+    let mut files: Vec<_> = path
+        .read_dir()?
+        .flatten()
+        .map(|file| file.path())
+        .filter(|path| is_config_file(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Load the configs of several sources together so they can `extends` each other by name
+///
+/// Configs overridden by a later config of the same name are left out.
+/// Every file gets its own result, so one broken config does not take down the rest. Warnings are logged
+pub fn load_config_sources(sources: &[ConfigSource]) -> Result<Vec<LoadedConfig>, ScrapeError> {
+    Ok(check_config_sources(sources)?
+        .into_iter()
+        .map(|(file, config, warnings)| {
+            for warning in warnings {
+                warn!("[config] {warning}");
+            }
+            (file, config)
+        })
+        .collect())
+}
+
+fn check_config_sources(sources: &[ConfigSource]) -> Result<Vec<CheckedConfig>, ScrapeError> {
+    let mut raw_configs = vec![];
+    for source in sources {
+        raw_configs.extend(source.read()?);
+    }
+
+    let mut by_name = HashMap::new();
+    let mut last_of_name = HashMap::new();
+    for (index, (_, raw)) in raw_configs.iter().enumerate() {
+        if let Ok(raw) = raw {
+            if let Some(name) = config_name(raw) {
+                by_name.insert(name.clone(), raw.clone());
+                last_of_name.insert(name, index);
+            }
+        }
+    }
+
+    Ok(raw_configs
+        .into_iter()
+        .enumerate()
+        .filter(|(index, (file, raw))| {
+            let Some(name) = raw.as_ref().ok().and_then(config_name) else {
+                return true;
+            };
+            let overridden = last_of_name[&name] != *index;
+            if overridden {
+                debug!("[config] {} is overridden by a later config named {name}", file.display());
+            }
+            !overridden
+        })
+        .map(|(_, (file, raw))| {
+            let mut warnings = vec![];
+            let config = raw
+                .and_then(|raw| resolve_extends(raw, &by_name, &mut vec![]))
+                .and_then(|table| {
+                    let config = Value::new(None, ValueKind::Table(table));
+                    let (errors, config_warnings) =
+                        validate::validate_config(&file, &serde_json::Value::deserialize(config.clone())?)
+                            .into_iter()
+                            .partition(ConfigIssue::is_error);
+                    warnings = config_warnings;
+                    if !errors.is_empty() {
+                        return Err(ScrapeError::InvalidConfig(errors));
+                    }
+                    MangaScraperConfig::deserialize(config).map_err(ScrapeError::from)
+                });
+            (file, config, warnings)
+        })
+        .collect())
+}
+
+pub(super) fn config_name(table: &Table) -> Option<String> {
+    table.get("name").and_then(|name| name.clone().into_string().ok())
+}
+
+fn read_raw_config(path: &Path) -> Result<Table, ScrapeError> {
+    let config = ConfigBuilder::<DefaultState>::default()
+        .add_source(File::from(path))
+        .build()?;
+    Ok(config.try_deserialize::<Table>()?)
+}
+
+fn parse_raw_config(text: &str, format: FileFormat) -> Result<Table, ScrapeError> {
+    let config = ConfigBuilder::<DefaultState>::default()
+        .add_source(File::from_str(text, format))
+        .build()?;
+    Ok(config.try_deserialize::<Table>()?)
+}
+
+/// Merge a config on top of the config it extends, following the chain up to the root
+fn resolve_extends(
+    table: Table,
+    by_name: &HashMap<String, Table>,
+    chain: &mut Vec<String>,
+) -> Result<Table, ScrapeError> {
+    let name = config_name(&table).unwrap_or_default();
+    chain.push(name.clone());
+
+    let Some(base_name) = table.get("extends").and_then(|base| base.clone().into_string().ok()) else {
+        return Ok(table);
+    };
+    if chain.contains(&base_name) {
+        chain.push(base_name);
+        return Err(ScrapeError::ConfigInheritanceCycle(chain.clone()));
+    }
+    let base = by_name
+        .get(&base_name)
+        .ok_or_else(|| ScrapeError::UnknownBaseConfig(name, base_name))?;
+    let mut base = resolve_extends(base.clone(), by_name, chain)?;
+    merge(&mut base, table);
+    Ok(base)
+}
+
+/// Deep merge `other` into `base`
+///
+/// Tables are merged key by key and any other value is replaced,
+/// except `search` entries, which are merged by position
+/// so a variant can swap out hostnames while keeping the selectors of its base
+fn merge(base: &mut Table, other: Table) {
+    for (key, value) in other {
+        let merge_arrays = key == "search";
+        match base.get_mut(&key) {
+            Some(base_value) => merge_value(base_value, value, merge_arrays),
+            None => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_value(base: &mut Value, value: Value, merge_arrays: bool) {
+    match (&mut base.kind, value.kind) {
+        (ValueKind::Table(base_table), ValueKind::Table(table)) => merge(base_table, table),
+        (ValueKind::Array(base_array), ValueKind::Array(array)) if merge_arrays => {
+            base_array.truncate(array.len());
+            for (index, item) in array.into_iter().enumerate() {
+                match base_array.get_mut(index) {
+                    Some(base_item) => merge_value(base_item, item, false),
+                    None => base_array.push(item),
+                }
+            }
+        }
+        (_, kind) => base.kind = kind,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{borrow::Cow, path::Path};
+
+    use super::ConfigSource;
+    use crate::{config::string_selector_options::StringSelection, error::ScrapeError};
+
+    #[test]
+    fn test_builtin_configs() {
+        let builtin = ConfigSource::builtin();
+        for file in super::config_files(Path::new("configs")).unwrap() {
+            let text = std::fs::read_to_string(&file).unwrap();
+            let built_in = builtin.iter().any(|source| {
+                matches!(source, ConfigSource::Text { name, text: builtin_text }
+                    if name.file_name() == file.file_name() && *builtin_text == text)
+            });
+            assert!(built_in, "{} is not built in", file.display());
+        }
+        assert_eq!(super::load_sources(&builtin).unwrap().len(), builtin.len());
+    }
+
+    #[test]
+    fn test_override() {
+        let madara = std::fs::read_to_string("configs/madara.yaml").unwrap();
+        let mut sources = ConfigSource::builtin();
+        sources.push(ConfigSource::Text {
+            name: "user/madara.yaml".into(),
+            text: Cow::Owned(madara.replace("name: madara", "name: madara\npriority: 5")),
+        });
+        let configs = super::load_sources(&sources).unwrap();
+
+        assert_eq!(configs.iter().filter(|config| config.name == "madara").count(), 1);
+        assert_eq!(configs[0].name, "madara");
+        assert_eq!(configs[0].priority, 5);
+    }
+
+    #[test]
+    fn test_extends() {
+        let configs = super::load_configs(Path::new("tests/fragments/config/extends")).unwrap();
+        let variant = configs.iter().find(|config| config.name == "madara-variant").unwrap();
+
+        assert_eq!(variant.extends.as_deref(), Some("madara"));
+        // Sorted by priority before name
+        assert_eq!(configs[0].name, "madara-variant");
+        // Overridden
+        assert_eq!(variant.manga.title.selectors.len(), 1);
+        assert_eq!(variant.manga.title.selectors[0].selector, "h1.variant-title");
+        let date = variant.manga.chapter.date.as_ref().unwrap();
+        assert!(matches!(date.selectors[0].options.text_selection, StringSelection::OwnText));
+        assert_eq!(variant.accept.hostnames, vec!["variant.example.com"]);
+        assert_eq!(variant.search[0].hostnames, vec!["variant.example.com"]);
+        // Inherited
+        assert_eq!(variant.manga.chapter.base.selectors[0].selector, "li.wp-manga-chapter");
+        assert_eq!(variant.manga.chapter.fetch_external.len(), 3);
+        assert_eq!(variant.images.image_selector.selectors.len(), 2);
+        assert_eq!(variant.search[0].selectors.base.selectors[0].selector, ".c-tabs-item__content");
+    }
+
+    #[test]
+    fn test_formats() {
+        let mut sources = ConfigSource::builtin();
+        sources.push(ConfigSource::Dir("tests/fragments/config/formats".into()));
+        let configs = super::load_sources(&sources).unwrap();
+
+        let variants: Vec<_> = ["yaml", "json", "toml"]
+            .into_iter()
+            .map(|format| {
+                let name = format!("variant-{format}");
+                let config = configs.iter().find(|config| config.name == name).unwrap();
+                format!("{config:?}").replace(&name, "variant")
+            })
+            .collect();
+        assert_eq!(variants[0], variants[1]);
+        assert_eq!(variants[0], variants[2]);
+    }
+
+    #[test]
+    fn test_extends_cycle() {
+        let error = super::load_configs(Path::new("tests/fragments/config/extends_cycle")).unwrap_err();
+        let ScrapeError::InvalidConfig(issues) = &error else {
+            panic!("{error}");
+        };
+        assert!(issues.iter().all(|issue| issue.message.contains("Config inheritance cycle")), "{error}");
+    }
+}
@@ -1,6 +1,13 @@
 use serde::Deserialize;
+use serde_json::{json, Value};
 
-use super::{array_selector::ArraySelectors, chapter::Chapter, string_selector::StringSelectors};
+use super::{
+    array_selector::ArraySelectors,
+    chapter::Chapter,
+    schema::{self, ConfigSchema, Location, SchemaGenerator},
+    status_map::StatusMap,
+    string_selector::StringSelectors,
+};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
@@ -9,8 +16,55 @@ pub struct Manga {
     pub description: StringSelectors,
     pub cover_url: Option<StringSelectors>,
     pub status: Option<StringSelectors>,
+    /// Maps the status text to a status
+    #[serde(default)]
+    pub status_map: StatusMap,
     pub authors: Option<ArraySelectors>,
+    pub artists: Option<ArraySelectors>,
     pub genres: Option<ArraySelectors>,
+    /// Text like "Mature" or "18+", see [`crate::model::ContentRating::from_text`]
+    pub content_rating: Option<StringSelectors>,
+    /// Text like "Manhwa" or "Korean", see [`crate::model::PublicationType::from_text`]
+    pub publication_type: Option<StringSelectors>,
+    /// Text with the year the manga started in it
+    pub year: Option<StringSelectors>,
+    pub original_language: Option<StringSelectors>,
     pub alt_titles: Option<ArraySelectors>,
     pub chapter: Chapter,
 }
+
+impl ConfigSchema for Manga {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "manga"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "description": "Scraper queries for a manga homepage",
+            "type": "object",
+            "required": ["title", "description", "chapter"],
+            "properties": {
+                "title": generator.subschema::<StringSelectors>(),
+                "description": generator.subschema::<StringSelectors>(),
+                "cover_url": generator.subschema::<Option<StringSelectors>>(),
+                "status": generator.subschema::<Option<StringSelectors>>(),
+                "status_map": generator.subschema::<StatusMap>(),
+                "authors": generator.subschema::<Option<ArraySelectors>>(),
+                "artists": generator.subschema::<Option<ArraySelectors>>(),
+                "genres": generator.subschema::<Option<ArraySelectors>>(),
+                "content_rating": generator.described::<Option<StringSelectors>>(
+                    "Rating like safe, suggestive, erotica (mature, 17+) or pornographic (adult, 18+)"
+                ),
+                "publication_type": generator.described::<Option<StringSelectors>>(
+                    "Type like manga, manhwa, manhua or webtoon, the country (Japanese, Korean, Chinese) works too"
+                ),
+                "year": generator.described::<Option<StringSelectors>>(
+                    "Text with the year the manga started in it, the first year in the text is used"
+                ),
+                "original_language": generator.described::<Option<StringSelectors>>(
+                    "Language the manga was written in, kept as the website writes it"
+                ),
+                "alt_titles": generator.subschema::<Option<ArraySelectors>>(),
+                "chapter": generator.subschema::<Chapter>(),
+            },
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use config::{Map, Value, ValueKind};
+
+use crate::error::ScrapeError;
+
+use super::loader::config_name;
+
+type Table = Map<String, Value>;
+
+/// Version of the config format, configs without a `version` key are version 1
+pub const CONFIG_VERSION: u32 = 1;
+
+/// A change to the config format, which turns a config of the version before it into a config of version `to`
+struct Migration {
+    to: u32,
+    description: &'static str,
+    migrate: fn(&mut Table),
+}
+
+/// Every change to the config format, oldest first
+///
+/// Renaming or restructuring a key bumps [`CONFIG_VERSION`] and adds a migration here,
+/// so configs written for an older version keep loading the way they were meant to
+const MIGRATIONS: [Migration; 0] = [];
+
+/// Bring a config (before `extends` is resolved) up to [`CONFIG_VERSION`]
+pub fn migrate(table: Table) -> Result<Table, ScrapeError> {
+    migrate_with(table, &MIGRATIONS, CONFIG_VERSION)
+}
+
+fn migrate_with(mut table: Table, migrations: &[Migration], current: u32) -> Result<Table, ScrapeError> {
+    let version = match table.get("version") {
+        Some(version) => version.clone().into_int()?,
+        None => 1,
+    };
+    if version < 1 || version > i64::from(current) {
+        return Err(ScrapeError::UnsupportedConfigVersion(version, current));
+    }
+
+    for migration in migrations.iter().filter(|migration| i64::from(migration.to) > version) {
+        debug!(
+            "[config] migrating {:?} to version {}: {}",
+            config_name(&table),
+            migration.to,
+            migration.description
+        );
+        (migration.migrate)(&mut table);
+    }
+    table.insert(
+        String::from("version"),
+        Value::new(None, ValueKind::U64(current.into())),
+    );
+    Ok(table)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use config::{builder::DefaultState, ConfigBuilder, File, FileFormat, Map, Value};
+
+    use super::{migrate, migrate_with, Migration, Table, CONFIG_VERSION};
+    use crate::{config::loader, error::ScrapeError};
+
+    fn table(yaml: &str) -> Table {
+        ConfigBuilder::<DefaultState>::default()
+            .add_source(File::from_str(yaml, FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize::<Map<String, Value>>()
+            .unwrap()
+    }
+
+    fn rename_alt_titles(table: &mut Table) {
+        let Some(manga) = table.get_mut("manga") else {
+            return;
+        };
+        let mut manga = manga.clone().into_table().unwrap();
+        if let Some(alt_titles) = manga.remove("alt_titles") {
+            manga.insert(String::from("alternative_titles"), alt_titles);
+        }
+        table.insert(String::from("manga"), manga.into());
+    }
+
+    const MIGRATIONS: [Migration; 2] = [
+        Migration {
+            to: 2,
+            description: "alt_titles is now alternative_titles",
+            migrate: rename_alt_titles,
+        },
+        Migration {
+            to: 3,
+            description: "priority moved into accept",
+            migrate: |table| {
+                if let Some(priority) = table.remove("priority") {
+                    let mut accept = table
+                        .remove("accept")
+                        .and_then(|accept| accept.into_table().ok())
+                        .unwrap_or_default();
+                    accept.insert(String::from("priority"), priority);
+                    table.insert(String::from("accept"), accept.into());
+                }
+            },
+        },
+    ];
+
+    #[test]
+    fn test_migrate() {
+        // Without a version it is version 1
+        let migrated = migrate_with(
+            table("name: a\npriority: 2\nmanga:\n  alt_titles: h2\n"),
+            &MIGRATIONS,
+            3,
+        )
+        .unwrap();
+        let manga = migrated["manga"].clone().into_table().unwrap();
+        assert_eq!(manga["alternative_titles"].clone().into_string().unwrap(), "h2");
+        assert!(!manga.contains_key("alt_titles"));
+        let accept = migrated["accept"].clone().into_table().unwrap();
+        assert_eq!(accept["priority"].clone().into_int().unwrap(), 2);
+        assert_eq!(migrated["version"].clone().into_int().unwrap(), 3);
+
+        // Only newer migrations apply
+        let migrated = migrate_with(table("name: a\nversion: 2\nmanga:\n  alt_titles: h2\n"), &MIGRATIONS, 3).unwrap();
+        assert!(migrated["manga"]
+            .clone()
+            .into_table()
+            .unwrap()
+            .contains_key("alt_titles"));
+
+        assert!(matches!(
+            migrate_with(table("name: a\nversion: 4\n"), &MIGRATIONS, 3),
+            Err(ScrapeError::UnsupportedConfigVersion(4, 3))
+        ));
+        assert!(matches!(
+            migrate_with(table("name: a\nversion: 0\n"), &MIGRATIONS, 3),
+            Err(ScrapeError::UnsupportedConfigVersion(0, 3))
+        ));
+    }
+
+    /// The bundled configs are of the current version, so without their version only the version is added
+    #[test]
+    fn test_migrate_bundled_configs() {
+        let paths = loader::config_files(Path::new("configs")).unwrap();
+        assert!(!paths.is_empty());
+        for path in paths {
+            let mut config = table(&std::fs::read_to_string(&path).unwrap());
+            config.remove("version");
+
+            let mut migrated = migrate(config.clone()).unwrap();
+            let version = migrated.remove("version").unwrap();
+            assert_eq!(version.into_int().unwrap(), i64::from(CONFIG_VERSION));
+            assert_eq!(migrated, config, "migrating {path:?} changed it");
+        }
+    }
+}
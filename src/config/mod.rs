@@ -1,29 +1,136 @@
+use std::{
+    cmp::Ordering,
+    sync::{Arc, RwLock},
+};
+
 use serde::Deserialize;
+use serde_json::{json, Value};
 
-use self::{accept::Accept, manga::Manga, search::SearchConfig, images::Images};
+use self::{
+    accept::Accept,
+    config_test::ConfigTest,
+    http::HttpConfig,
+    image_urls::ImageUrls,
+    images::Images,
+    manga::Manga,
+    schema::{ConfigSchema, Location, SchemaGenerator},
+    search::SearchConfig,
+};
 
 pub mod accept;
 pub mod array_selector;
 pub mod array_selector_options;
 pub mod chapter;
+pub mod config_test;
+pub mod http;
+pub mod image_urls;
 pub mod manga;
+pub mod migrate;
 pub mod search;
+pub mod status_map;
 pub mod images;
+pub mod loader;
+pub mod request;
+pub mod schema;
 pub mod string_selector;
 pub mod string_selector_options;
+pub mod transform;
+pub mod validate;
+#[cfg(feature = "watch_dir")]
+pub mod watcher;
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
 pub struct MangaScraperConfig {
     pub name: String,
+    /// Name of the config this one is based on
+    #[serde(default)]
+    pub extends: Option<String>,
+    /// Configs with a higher priority are tried first
+    #[serde(default)]
+    pub priority: i32,
     pub accept: Accept,
     pub manga: Manga,
     pub images: Images,
+    /// Rewrites the URLs of chapter images and covers
+    #[serde(default)]
+    pub image_urls: ImageUrls,
     #[serde(default)]
     pub search: Vec<SearchConfig>,
     pub date_formats: Vec<String>,
+    /// HTTP settings for every request made with this config
+    #[serde(default)]
+    pub http: HttpConfig,
+    /// Offline tests of this config
+    #[serde(default)]
+    pub tests: Vec<ConfigTest>,
+}
+
+impl MangaScraperConfig {
+    /// Order in which configs are tried, by priority and then by name
+    pub fn cmp_priority(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority).then_with(|| self.name.cmp(&other.name))
+    }
 }
 
+impl ConfigSchema for MangaScraperConfig {
+    const LOCATION: Option<Location> = Some(Location::root(schema::CONFIG_SCHEMA));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "title": "Manga Scraper",
+            "description": "A manga scraper configuration",
+            "type": "object",
+            "required": ["name"],
+            "if": { "not": { "required": ["extends"] } },
+            "then": { "required": ["accept", "manga", "images", "date_formats"] },
+            "properties": {
+                "$schema": generator.described::<String>(
+                    "Schema of the config for editors, JSON configs use schema/config.schema.json"
+                ),
+                "version": {
+                    "description": "Version of the config format, configs written for an older version \
+                        are migrated when they are loaded",
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": migrate::CONFIG_VERSION,
+                    "default": 1,
+                },
+                "name": generator.subschema::<String>(),
+                "extends": generator.described::<String>(
+                    "Name of the config this one is based on. Tables are merged deeply, \
+                    other values (like selector lists) replace the inherited value \
+                    and search entries are merged by position"
+                ),
+                "priority": {
+                    "description": "Configs with a higher priority are tried first, configs with the same priority \
+                        are tried by name with the configs for the hostname before those matching the page",
+                    "type": "integer",
+                    "default": 0,
+                },
+                "accept": generator.subschema::<Accept>(),
+                "manga": generator.subschema::<Manga>(),
+                "images": generator.subschema::<Images>(),
+                "image_urls": generator.subschema::<ImageUrls>(),
+                "search": generator.subschema::<Vec<SearchConfig>>(),
+                "date_formats": {
+                    "type": "array",
+                    "description": "Date formats the website uses based on https://docs.rs/chrono/latest/chrono/format/strftime/index.html",
+                    "uniqueItems": true,
+                    "items": generator.subschema::<String>(),
+                },
+                "http": generator.subschema::<HttpConfig>(),
+                "tests": generator.described::<Vec<ConfigTest>>(
+                    "Offline tests of this config, run against the fixture files of every page"
+                ),
+            },
+        })
+    }
+}
+
+/// Config list shared between a scraper and its watcher
+pub type SharedConfigs = Arc<RwLock<Vec<Arc<MangaScraperConfig>>>>;
+
 #[cfg(test)]
 mod test {
     use std::path::Path;
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::schema::{self, ConfigSchema, Location, SchemaGenerator};
+
+lazy_static::lazy_static! {
+    static ref PLACEHOLDER: Regex = Regex::new(r"\{(?<name>\w+)\}").unwrap();
+}
+
+/// Body sent with a request, every string in it is a template (see [`interpolate`])
+///
+/// ```yaml
+/// body:
+///   form:
+///     action: manga_get_chapters
+///     manga: "{id}"
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestBody {
+    /// Form encoded fields
+    Form(HashMap<String, String>),
+    /// JSON document
+    Json(Value),
+    /// Sent as is, set a `Content-Type` header to go with it
+    Raw(String),
+}
+
+impl RequestBody {
+    /// Copy of the body with the placeholders filled in
+    pub fn render(&self, variables: &[(&str, &str)]) -> Self {
+        match self {
+            RequestBody::Form(fields) => RequestBody::Form(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), interpolate(value, variables)))
+                    .collect(),
+            ),
+            RequestBody::Json(value) => RequestBody::Json(render_json(value, variables)),
+            RequestBody::Raw(body) => RequestBody::Raw(interpolate(body, variables)),
+        }
+    }
+
+    /// Check if a placeholder like `{page}` is used anywhere in the body
+    pub fn uses(&self, placeholder: &str) -> bool {
+        match self {
+            RequestBody::Form(fields) => fields.values().any(|value| value.contains(placeholder)),
+            RequestBody::Json(value) => json_uses(value, placeholder),
+            RequestBody::Raw(body) => body.contains(placeholder),
+        }
+    }
+}
+
+/// The placeholders every template can use, those that do not apply to the request are empty
+///
+/// `{id}` is the id found by `fetch_external`, `{host}` (or `{hostname}`) and `{url}` are from the page the request is
+/// made for and `{query}` is the search query. Search requests add `{page}`
+pub fn variables<'a>(id: &'a str, url: &'a Url, query: &'a str) -> Vec<(&'static str, &'a str)> {
+    let host = url.host_str().unwrap_or_default();
+    vec![("id", id), ("host", host), ("hostname", host), ("url", url.as_str()), ("query", query)]
+}
+
+/// Replace every `{name}` in a template with its value, in one pass so that values are never replaced in turn
+///
+/// Placeholders without a value are left as they are
+pub fn interpolate(template: &str, variables: &[(&str, &str)]) -> String {
+    PLACEHOLDER
+        .replace_all(template, |captures: &Captures| {
+            let name = &captures["name"];
+            match variables.iter().find(|(variable, _)| *variable == name) {
+                Some((_, value)) => value.to_string(),
+                None => captures[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Fill in the headers of a request
+pub fn render_headers(headers: &HashMap<String, String>, variables: &[(&str, &str)]) -> HashMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.clone(), interpolate(value, variables)))
+        .collect()
+}
+
+fn render_json(value: &Value, variables: &[(&str, &str)]) -> Value {
+    match value {
+        Value::String(text) => Value::String(interpolate(text, variables)),
+        Value::Array(items) => Value::Array(items.iter().map(|item| render_json(item, variables)).collect()),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| (key.clone(), render_json(value, variables)))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+fn json_uses(value: &Value, placeholder: &str) -> bool {
+    match value {
+        Value::String(text) => text.contains(placeholder),
+        Value::Array(items) => items.iter().any(|item| json_uses(item, placeholder)),
+        Value::Object(object) => object.values().any(|value| json_uses(value, placeholder)),
+        _ => false,
+    }
+}
+
+impl ConfigSchema for RequestBody {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "request_body"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "description": "Body sent with the request, strings in it are templates like the URL",
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["form"],
+                    "properties": {
+                        "form": generator.described::<HashMap<String, String>>("Form encoded fields"),
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["json"],
+                    "properties": {
+                        "json": { "description": "JSON document" },
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["raw"],
+                    "properties": {
+                        "raw": generator.described::<String>("Sent as is, set a Content-Type header to go with it"),
+                    },
+                },
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use config::{builder::DefaultState, ConfigBuilder, File, FileFormat};
+    use reqwest::Url;
+    use serde_json::json;
+
+    use super::{interpolate, variables, RequestBody};
+
+    #[test]
+    fn test_render_body() {
+        let body = ConfigBuilder::<DefaultState>::default()
+            .add_source(File::from_str(
+                "form:\n  action: manga_get_chapters\n  manga: '{id}'\n",
+                FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize::<RequestBody>()
+            .unwrap();
+        assert!(body.uses("{id}"));
+        let RequestBody::Form(fields) = body.render(&[("id", "42")]) else {
+            panic!("Expected a form body");
+        };
+        assert_eq!(
+            fields,
+            HashMap::from([
+                (String::from("action"), String::from("manga_get_chapters")),
+                (String::from("manga"), String::from("42")),
+            ])
+        );
+
+        let body = RequestBody::Json(json!({ "query": "{query}", "page": 1, "filters": ["{query}"] }));
+        let RequestBody::Json(value) = body.render(&[("query", "solo leveling")]) else {
+            panic!("Expected a JSON body");
+        };
+        assert_eq!(value, json!({ "query": "solo leveling", "page": 1, "filters": ["solo leveling"] }));
+        assert!(!body.uses("{page}"));
+    }
+
+    #[test]
+    fn test_variables() {
+        let url = Url::parse("https://example.com/manga/solo-leveling/").unwrap();
+        let variables = variables("42", &url, "solo leveling");
+        assert_eq!(
+            interpolate("https://{host}/ajax/{id}?q={query}", &variables),
+            "https://example.com/ajax/42?q=solo leveling"
+        );
+        assert_eq!(interpolate("{hostname}", &variables), "example.com");
+        assert_eq!(interpolate("{url}chapters", &variables), "https://example.com/manga/solo-leveling/chapters");
+
+        // Values are not templates themselves
+        let variables = super::variables("{url}", &url, "{host} {page}");
+        assert_eq!(interpolate("{id}/{query}/{unknown}", &variables), "{url}/{host} {page}/{unknown}");
+    }
+}
@@ -0,0 +1,406 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use yaml_rust2::{yaml::Hash, Yaml, YamlEmitter};
+
+use super::{array_selector::ArraySelectors, string_selector::StringSelectors, MangaScraperConfig};
+
+pub const CONFIG_SCHEMA: &str = "config.schema.yaml";
+pub const STRING_SELECTOR_SCHEMA: &str = "string_selector.schema.yaml";
+pub const ARRAY_SELECTOR_SCHEMA: &str = "array_selector.schema.yaml";
+
+/// A config type that describes the YAML it deserializes from as a JSON schema
+///
+/// The files in `schema/` are generated from these, see [`schema_documents`]
+pub trait ConfigSchema {
+    /// Where the schema is written to, types without a location are inlined where they are used
+    const LOCATION: Option<Location> = None;
+
+    fn schema(generator: &SchemaGenerator) -> Value;
+}
+
+/// A schema file and, unless the type is the root of the file, its name in `$defs`
+#[derive(Clone, Copy, PartialEq)]
+pub struct Location {
+    pub file: &'static str,
+    pub definition: Option<&'static str>,
+}
+
+impl Location {
+    pub const fn root(file: &'static str) -> Self {
+        Self { file, definition: None }
+    }
+
+    pub const fn definition(file: &'static str, name: &'static str) -> Self {
+        Self {
+            file,
+            definition: Some(name),
+        }
+    }
+}
+
+type SchemaFn = fn(&SchemaGenerator) -> Value;
+
+/// Generates the schema of one location, collecting the other locations it references
+pub struct SchemaGenerator {
+    file: &'static str,
+    referenced: RefCell<Vec<(Location, SchemaFn)>>,
+}
+
+impl SchemaGenerator {
+    fn new(file: &'static str) -> Self {
+        Self {
+            file,
+            referenced: RefCell::default(),
+        }
+    }
+
+    /// The schema of `T`, a `$ref` when it has a location of its own
+    pub fn subschema<T: ConfigSchema>(&self) -> Value {
+        let Some(location) = T::LOCATION else {
+            return T::schema(self);
+        };
+        self.referenced.borrow_mut().push((location, T::schema));
+
+        let file = if location.file == self.file { "" } else { location.file };
+        let reference = match location.definition {
+            Some(name) => format!("{file}#/$defs/{name}"),
+            None if file.is_empty() => String::from("#"),
+            None => file.to_string(),
+        };
+        json!({ "$ref": reference })
+    }
+
+    /// The schema of `T` with a description
+    pub fn described<T: ConfigSchema>(&self, description: &str) -> Value {
+        let mut schema = self.subschema::<T>();
+        schema["description"] = json!(description);
+        schema
+    }
+}
+
+impl ConfigSchema for String {
+    fn schema(_generator: &SchemaGenerator) -> Value {
+        json!({ "type": "string" })
+    }
+}
+
+impl ConfigSchema for Regex {
+    fn schema(_generator: &SchemaGenerator) -> Value {
+        json!({ "type": "string", "format": "regex" })
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for Vec<T> {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({ "type": "array", "items": generator.subschema::<T>() })
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for HashMap<String, T> {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({ "type": "object", "additionalProperties": generator.subschema::<T>() })
+    }
+}
+
+impl<T: ConfigSchema> ConfigSchema for Option<T> {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        generator.subschema::<T>()
+    }
+}
+
+/// The schema files in `schema/` by file name
+pub fn schema_documents() -> Vec<(&'static str, Value)> {
+    let roots: [(Location, SchemaFn); 3] = [
+        (Location::root(CONFIG_SCHEMA), MangaScraperConfig::schema),
+        (Location::root(STRING_SELECTOR_SCHEMA), StringSelectors::schema),
+        (Location::root(ARRAY_SELECTOR_SCHEMA), ArraySelectors::schema),
+    ];
+
+    // Generate every location reachable from the roots, in the order they are first referenced
+    let mut pending = roots.to_vec();
+    let mut generated: Vec<(Location, Value)> = vec![];
+    while !pending.is_empty() {
+        let (location, schema) = pending.remove(0);
+        if generated.iter().any(|(generated, _)| *generated == location) {
+            continue;
+        }
+        let generator = SchemaGenerator::new(location.file);
+        let value = schema(&generator);
+        pending.extend(generator.referenced.into_inner());
+        generated.push((location, value));
+    }
+
+    roots
+        .iter()
+        .map(|(root, _)| {
+            let mut document = Map::new();
+            document.insert(String::from("$id"), json!(root.file));
+            let mut definitions = Map::new();
+            for (location, value) in generated.iter().filter(|(location, _)| location.file == root.file) {
+                match (location.definition, value) {
+                    (None, Value::Object(root_schema)) => document.extend(root_schema.clone()),
+                    (Some(name), value) => {
+                        definitions.insert(name.to_string(), value.clone());
+                    }
+                    _ => {}
+                }
+            }
+            if !definitions.is_empty() {
+                document.insert(String::from("$defs"), Value::Object(definitions));
+            }
+            (root.file, Value::Object(document))
+        })
+        .collect()
+}
+
+/// Name of the JSON copy of a schema file, for editors of JSON and TOML configs
+pub fn json_file(file: &str) -> String {
+    file.replace(".schema.yaml", ".schema.json")
+}
+
+/// Write a schema as JSON, with its `$id` and `$ref`s pointing at the JSON copies of the schema files
+pub fn to_json(value: &Value) -> String {
+    fn point_at_json(value: &mut Value) {
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    match (key.as_str(), &*value) {
+                        ("$id" | "$ref", Value::String(reference)) => *value = Value::String(json_file(reference)),
+                        _ => point_at_json(value),
+                    }
+                }
+            }
+            Value::Array(array) => array.iter_mut().for_each(point_at_json),
+            _ => {}
+        }
+    }
+
+    let mut value = value.clone();
+    point_at_json(&mut value);
+    serde_json::to_string_pretty(&value).expect("a JSON value always serializes") + "\n"
+}
+
+/// Write a schema as YAML
+pub fn to_yaml(value: &Value) -> String {
+    let mut yaml = String::new();
+    YamlEmitter::new(&mut yaml)
+        .dump(&yaml_value(value))
+        .expect("writing to a string does not fail");
+    // The emitter starts the document with `---` and leaves out the final newline
+    yaml.strip_prefix("---\n").unwrap_or(&yaml).to_string() + "\n"
+}
+
+fn yaml_value(value: &Value) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(bool) => Yaml::Boolean(*bool),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => Yaml::Integer(integer),
+            None => Yaml::Real(number.to_string()),
+        },
+        Value::String(string) => Yaml::String(string.clone()),
+        Value::Array(items) => Yaml::Array(items.iter().map(yaml_value).collect()),
+        Value::Object(object) => Yaml::Hash(
+            object
+                .iter()
+                .map(|(key, value)| (Yaml::String(key.clone()), yaml_value(value)))
+                .collect::<Hash>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, path::Path};
+
+    use config::{builder::DefaultState, ConfigBuilder, File, FileFormat};
+    use serde::de::DeserializeOwned;
+    use serde_json::{json, Map, Value};
+
+    use crate::config::{
+        array_selector::ArraySelectors, string_selector::StringSelectors, validate::Schemas, MangaScraperConfig,
+    };
+
+    use super::{ARRAY_SELECTOR_SCHEMA, CONFIG_SCHEMA, STRING_SELECTOR_SCHEMA};
+
+    /// Properties the loader reads before the config is deserialized
+    const READ_BY_LOADER: [&str; 2] = ["/$schema", "/version"];
+
+    /// Builds a document that uses every property of a schema
+    struct Sample<'a> {
+        schemas: &'a Schemas,
+        /// Which alternative of every `oneOf`, `anyOf` and `enum` is used
+        choice: usize,
+        /// JSON pointers to the properties in the document
+        properties: Vec<String>,
+        /// The `$ref`s being followed
+        references: Vec<String>,
+    }
+
+    impl Sample<'_> {
+        fn value(&mut self, document: &str, schema: &Value, pointer: &str, repeated: bool) -> Value {
+            let reference = schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .map(|reference| format!("{document}{reference}"));
+            // Selectors nest through pagination, a schema inside of itself only gets its required properties
+            let repeated = repeated || reference.as_ref().is_some_and(|reference| self.references.contains(reference));
+            self.references.extend(reference.clone());
+            let value = self.followed_value(document, schema, pointer, repeated);
+            if reference.is_some() {
+                self.references.pop();
+            }
+            value
+        }
+
+        fn followed_value(&mut self, document: &str, schema: &Value, pointer: &str, repeated: bool) -> Value {
+            let (document, schema) = self.schemas.follow(document, schema);
+            let choice = if repeated { 0 } else { self.choice };
+            if let Some(value) = schema.get("const") {
+                return value.clone();
+            }
+            if let Some(Value::Array(values)) = schema.get("enum") {
+                return values[choice % values.len()].clone();
+            }
+            for keyword in ["oneOf", "anyOf"] {
+                // Alternatives that only require properties, like those of pagination, do not describe a value
+                let alternatives: Vec<&Value> = schema
+                    .get(keyword)
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter(|alternative| {
+                        ["type", "$ref", "enum", "const", "properties"]
+                            .iter()
+                            .any(|keyword| alternative.get(keyword).is_some())
+                    })
+                    .collect();
+                if !alternatives.is_empty() {
+                    return self.value(document, alternatives[choice % alternatives.len()], pointer, repeated);
+                }
+            }
+
+            let example = schema
+                .get("examples")
+                .and_then(|examples| examples.get(0))
+                .or_else(|| schema.get("default"))
+                .cloned();
+            match schema.get("type").and_then(Value::as_str) {
+                Some("object") => {
+                    let required = schema.get("required").and_then(Value::as_array);
+                    let is_required = |name: &String| required.is_some_and(|required| required.contains(&json!(name)));
+                    let mut object = Map::new();
+                    for (name, property) in schema.get("properties").and_then(Value::as_object).into_iter().flatten() {
+                        if repeated && !is_required(name) {
+                            continue;
+                        }
+                        let pointer = format!("{pointer}/{name}");
+                        object.insert(name.clone(), self.value(document, property, &pointer, repeated));
+                        // Properties that take any value, like a JSON request body, cannot be invalid
+                        let (_, property) = self.schemas.follow(document, property);
+                        let keywords = ["type", "enum", "const", "oneOf", "anyOf"];
+                        if keywords.iter().any(|keyword| property.get(keyword).is_some()) {
+                            self.properties.push(pointer);
+                        }
+                    }
+                    if let Some(additional @ Value::Object(_)) = schema.get("additionalProperties") {
+                        let value = self.value(document, additional, &format!("{pointer}/a"), repeated);
+                        object.insert(String::from("a"), value);
+                    }
+                    Value::Object(object)
+                }
+                Some("array") => json!([self.value(document, &schema["items"], &format!("{pointer}/0"), repeated)]),
+                Some("string") => example.unwrap_or_else(|| match schema.get("format").and_then(Value::as_str) {
+                    Some("date") => json!("2024-01-01"),
+                    // Also a regex that is quick to compile, as every sample compiles all of them
+                    _ => json!("a"),
+                }),
+                Some("integer") => example.unwrap_or(json!(1)),
+                Some("number") => example.unwrap_or(json!(1.5)),
+                Some("boolean") => example.unwrap_or(json!(true)),
+                _ => Value::Null,
+            }
+        }
+    }
+
+    /// The type accepts a document using every property of its schema, and rejects it when any property is invalid
+    fn check_properties<T: DeserializeOwned>(
+        schemas: &Schemas,
+        file: &str,
+        choice: usize,
+        checked: &mut HashSet<String>,
+    ) {
+        let mut sample = Sample {
+            schemas,
+            choice,
+            properties: vec![],
+            references: vec![],
+        };
+        let document = sample.value(file, &json!({ "$ref": file }), "", false);
+        if let Err(e) = serde_json::from_value::<T>(document.clone()) {
+            panic!("{file} allows a document its type rejects: {e}\n{document:#}");
+        }
+
+        let properties = sample.properties.into_iter();
+        let unchecked = properties.filter(|pointer| !READ_BY_LOADER.contains(&pointer.as_str()));
+        for pointer in unchecked.filter(|pointer| checked.insert(pointer.clone())) {
+            let mut invalid = document.clone();
+            *invalid.pointer_mut(&pointer).unwrap() = json!([[[]]]);
+            assert!(
+                serde_json::from_value::<T>(invalid).is_err(),
+                "{file} describes {pointer}, which its type does not read"
+            );
+        }
+    }
+
+    #[test]
+    fn test_schema_properties() {
+        let schemas = Schemas::new();
+        let mut checked = [HashSet::new(), HashSet::new(), HashSet::new()];
+        for choice in 0..6 {
+            check_properties::<MangaScraperConfig>(&schemas, CONFIG_SCHEMA, choice, &mut checked[0]);
+            check_properties::<StringSelectors>(&schemas, STRING_SELECTOR_SCHEMA, choice, &mut checked[1]);
+            check_properties::<ArraySelectors>(&schemas, ARRAY_SELECTOR_SCHEMA, choice, &mut checked[2]);
+        }
+    }
+
+    /// Fails when the types changed without regenerating the schemas, `UPDATE_SCHEMAS=1 cargo test schema` rewrites them
+    #[test]
+    fn test_schemas_up_to_date() {
+        for (file, schema) in super::schema_documents() {
+            let yaml = super::to_yaml(&schema);
+            let path = Path::new("schema").join(file);
+            if std::env::var_os("UPDATE_SCHEMAS").is_some() {
+                std::fs::write(&path, &yaml).unwrap();
+            }
+
+            let parsed = ConfigBuilder::<DefaultState>::default()
+                .add_source(File::from_str(&yaml, FileFormat::Yaml))
+                .build()
+                .unwrap()
+                .try_deserialize::<Value>()
+                .unwrap();
+            assert_eq!(parsed, schema, "{file} does not survive the YAML round trip");
+
+            let checked_in = std::fs::read_to_string(&path).unwrap();
+            assert!(
+                checked_in == yaml,
+                "{file} is stale, run `UPDATE_SCHEMAS=1 cargo test schema` to regenerate it"
+            );
+
+            let json_file = super::json_file(file);
+            let json = super::to_json(&schema);
+            let json_path = Path::new("schema").join(&json_file);
+            if std::env::var_os("UPDATE_SCHEMAS").is_some() {
+                std::fs::write(&json_path, &json).unwrap();
+            }
+            let checked_in = std::fs::read_to_string(&json_path).unwrap_or_default();
+            assert!(
+                checked_in == json,
+                "{json_file} is stale, run `UPDATE_SCHEMAS=1 cargo test schema` to regenerate it"
+            );
+        }
+    }
+}
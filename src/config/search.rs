@@ -1,6 +1,15 @@
+use std::collections::HashMap;
+
 use serde::Deserialize;
+use serde_json::{json, Value};
 
-use super::{string_selector::StringSelectors, string_selector_options::CleanupOption};
+use super::{
+    chapter::{self, Pagination},
+    request::RequestBody,
+    schema::{self, ConfigSchema, Location, SchemaGenerator},
+    string_selector::StringSelectors,
+    string_selector_options::CleanupOption,
+};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
@@ -19,8 +28,76 @@ pub struct SearchSelectors {
 pub struct SearchConfig {
     #[serde(default)]
     pub hostnames: Vec<String>,
+    /// URL of the results, `{query}`, `{page}` (starting at 1) and the other variables of
+    /// [`super::request::variables`] are filled in
     pub search_url: String,
+    #[serde(default = "chapter::default_method")]
+    pub method: String,
+    /// Headers sent with the request, filled in like `search_url`
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Body sent with the request, filled in like `search_url`
+    #[serde(default)]
+    pub body: Option<RequestBody>,
     #[serde(default)]
     pub query_format: Vec<CleanupOption>,
     pub selectors: SearchSelectors,
+    /// How to get to the next pages of results when the request has no `{page}`
+    #[serde(default)]
+    pub pagination: Option<Pagination>,
+}
+
+impl SearchConfig {
+    /// Check if the page number is part of the request, so any page can be requested directly
+    pub fn is_paged(&self) -> bool {
+        self.search_url.contains("{page}") || self.body.as_ref().is_some_and(|body| body.uses("{page}"))
+    }
+}
+
+impl ConfigSchema for SearchSelectors {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "required": ["base", "url", "title"],
+            "properties": {
+                "base": generator.subschema::<StringSelectors>(),
+                "url": generator.subschema::<StringSelectors>(),
+                "title": generator.subschema::<StringSelectors>(),
+                "cover_url": generator.subschema::<Option<StringSelectors>>(),
+                "posted": generator.subschema::<Option<StringSelectors>>(),
+            },
+        })
+    }
+}
+
+impl ConfigSchema for SearchConfig {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "search"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        let mut search_url = generator.described::<String>(
+            "URL of the results, {query}, {page} (starting at 1), {host} (or {hostname}), {url} (the root of the website) and {id} (empty) are filled in",
+        );
+        search_url["examples"] = json!(["{host}/search?q={query}", "{url}search?q={query}&page={page}"]);
+
+        json!({
+            "type": "object",
+            "required": ["search_url", "selectors"],
+            "properties": {
+                "hostnames": generator.subschema::<Vec<String>>(),
+                "search_url": search_url,
+                "method": chapter::method_schema(),
+                "headers": generator.described::<HashMap<String, String>>(
+                    "Headers sent with the request, filled in like search_url"
+                ),
+                "body": generator.described::<Option<RequestBody>>(
+                    "Body sent with the request, filled in like search_url"
+                ),
+                "query_format": generator.subschema::<Vec<CleanupOption>>(),
+                "selectors": generator.subschema::<SearchSelectors>(),
+                "pagination": generator.described::<Option<Pagination>>(
+                    "How to get to the next pages of results when the request has no {page}"
+                ),
+            },
+        })
+    }
 }
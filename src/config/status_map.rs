@@ -0,0 +1,160 @@
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::model::MangaStatus;
+
+use super::schema::{self, ConfigSchema, Location, SchemaGenerator};
+
+/// Words and regexes that map the status text of a website to a [`MangaStatus`]
+///
+/// Checked before the English words every config understands
+///
+/// ```yaml
+/// status_map:
+///   ongoing:
+///     - En curso
+///     - 連載中
+///   completed:
+///     - regex: (?i)^complet
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, Default)]
+pub struct StatusMap {
+    #[serde(default)]
+    pub ongoing: Vec<StatusMatch>,
+    #[serde(default)]
+    pub completed: Vec<StatusMatch>,
+    #[serde(default)]
+    pub hiatus: Vec<StatusMatch>,
+    #[serde(default)]
+    pub cancelled: Vec<StatusMatch>,
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum StatusMatch {
+    /// Matches anywhere in the text
+    Regex {
+        #[serde(deserialize_with = "serde_regex::deserialize")]
+        regex: Regex,
+    },
+    /// Matches the whole text, ignoring case
+    Word(String),
+}
+
+/// Status words used by most English websites
+const DEFAULT_WORDS: [(MangaStatus, &[&str]); 4] = [
+    (
+        MangaStatus::Ongoing,
+        &["ongoing", "on-going", "on going", "updating", "live", "releasing", "publishing"],
+    ),
+    (MangaStatus::Completed, &["completed", "complete", "finished", "ended"]),
+    (MangaStatus::Hiatus, &["hiatus", "on hiatus", "on hold", "paused"]),
+    (MangaStatus::Cancelled, &["cancelled", "canceled", "dropped", "discontinued"]),
+];
+
+impl StatusMatch {
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            StatusMatch::Regex { regex } => regex.is_match(text),
+            StatusMatch::Word(word) => word.trim().to_lowercase() == text.to_lowercase(),
+        }
+    }
+}
+
+impl StatusMap {
+    /// The status the text stands for, [`MangaStatus::Unknown`] when nothing matches
+    pub fn status(&self, text: &str) -> MangaStatus {
+        let text = text.trim();
+        let mapped = [
+            (MangaStatus::Ongoing, &self.ongoing),
+            (MangaStatus::Completed, &self.completed),
+            (MangaStatus::Hiatus, &self.hiatus),
+            (MangaStatus::Cancelled, &self.cancelled),
+        ]
+        .into_iter()
+        .find(|(_, matches)| matches.iter().any(|status_match| status_match.matches(text)))
+        .map(|(status, _)| status);
+
+        mapped
+            .or_else(|| {
+                let text = text.to_lowercase();
+                DEFAULT_WORDS
+                    .into_iter()
+                    .find(|(_, words)| words.contains(&text.as_str()))
+                    .map(|(status, _)| status)
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl ConfigSchema for StatusMap {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::CONFIG_SCHEMA, "status_map"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        let matches = || generator.subschema::<Vec<StatusMatch>>();
+        json!({
+            "description": "Words and regexes for the status text of the website, \
+                checked before the English words like ongoing, completed, hiatus and cancelled",
+            "type": "object",
+            "properties": {
+                "ongoing": matches(),
+                "completed": matches(),
+                "hiatus": matches(),
+                "cancelled": matches(),
+            },
+        })
+    }
+}
+
+impl ConfigSchema for StatusMatch {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "oneOf": [
+                {
+                    "type": "string",
+                    "description": "Matches the whole text, ignoring case",
+                },
+                {
+                    "type": "object",
+                    "required": ["regex"],
+                    "properties": {
+                        "regex": generator.described::<Regex>("Matches anywhere in the text"),
+                    },
+                },
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::{builder::DefaultState, ConfigBuilder, File, FileFormat};
+
+    use super::StatusMap;
+    use crate::model::MangaStatus;
+
+    #[test]
+    fn test_status_map() {
+        let status_map = ConfigBuilder::<DefaultState>::default()
+            .add_source(File::from_str(
+                "ongoing: [En curso, 連載中]\ncompleted: [Completado]\nhiatus: [{ regex: '(?i)pausad[oa]' }]\n",
+                FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize::<StatusMap>()
+            .unwrap();
+
+        assert_eq!(status_map.status(" en curso "), MangaStatus::Ongoing);
+        assert_eq!(status_map.status("連載中"), MangaStatus::Ongoing);
+        assert_eq!(status_map.status("Completado"), MangaStatus::Completed);
+        assert_eq!(status_map.status("Serie pausada"), MangaStatus::Hiatus);
+        assert_eq!(status_map.status("On-Going"), MangaStatus::Ongoing);
+        assert_eq!(status_map.status("Dropped"), MangaStatus::Cancelled);
+        assert_eq!(status_map.status("Licenciado"), MangaStatus::Unknown);
+        assert_eq!(StatusMap::default().status("Completed"), MangaStatus::Completed);
+    }
+}
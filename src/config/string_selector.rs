@@ -1,6 +1,10 @@
 use serde::Deserialize;
+use serde_json::{json, Value};
 
-use super::string_selector_options::StringSelectorOptions;
+use super::{
+    schema::{self, ConfigSchema, Location, SchemaGenerator},
+    string_selector_options::StringSelectorOptions,
+};
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 pub struct StringSelector {
@@ -31,7 +35,7 @@ impl<'de> Deserialize<'de> for StringSelectors {
                 where
                     A: serde::de::SeqAccess<'de>, {
                 let mut selectors = vec![];
-                while let Ok(Some(element)) = seq.next_element() {
+                while let Some(element) = seq.next_element()? {
                     selectors.push(element);
                 }
 
@@ -74,7 +78,7 @@ impl<'de> Deserialize<'de> for StringSelectors {
                             options = Some(map.next_value()?);
                         }
                         _ => {
-                            // Ignore unknown fields
+                            // Unknown fields are reported as warnings when the config is validated
                             let _ = map.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
@@ -142,7 +146,7 @@ impl<'de> Deserialize<'de> for StringSelector {
                             options = Some(map.next_value()?);
                         }
                         _ => {
-                            // Ignore unknown fields
+                            // Unknown fields are reported as warnings when the config is validated
                             let _ = map.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
@@ -162,6 +166,51 @@ impl<'de> Deserialize<'de> for StringSelector {
     }
 }
 
+impl ConfigSchema for StringSelectors {
+    const LOCATION: Option<Location> = Some(Location::root(schema::STRING_SELECTOR_SCHEMA));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "title": "String Selector",
+            "description": "Select an element and extract their text",
+            "oneOf": [
+                {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {
+                        "anyOf": [
+                            generator.subschema::<StringSelector>(),
+                            selector_string(generator),
+                        ],
+                    },
+                },
+                generator.subschema::<StringSelector>(),
+                selector_string(generator),
+            ],
+        })
+    }
+}
+
+impl ConfigSchema for StringSelector {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::STRING_SELECTOR_SCHEMA, "selector"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "required": ["selector"],
+            "properties": {
+                "selector": selector_string(generator),
+                "options": generator.subschema::<StringSelectorOptions>(),
+            },
+        })
+    }
+}
+
+/// The plain string form of a selector
+fn selector_string(generator: &SchemaGenerator) -> Value {
+    generator.described::<String>("Query selector, or a JSON path like $.data.chapters[*] when the response is JSON")
+}
+
 #[cfg(test)]
 mod test {
     use std::path::Path;
@@ -1,5 +1,14 @@
 use regex::Regex;
 use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{
+    schema::{self, ConfigSchema, Location, SchemaGenerator},
+    transform::Transform,
+};
+
+/// What `all-text` joins the texts of multiple elements with, unless `join_with` is set
+const DEFAULT_JOIN_WITH: &str = " ";
 
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
@@ -14,6 +23,33 @@ pub struct StringSelectorOptions {
     /// Determine how text should be selected
     #[serde(default)]
     pub text_selection: StringSelection,
+    /// Parse a value out of the selected script
+    #[serde(default)]
+    pub script: Option<ScriptExtraction>,
+    /// Steps applied in order to the selected text, before it is trimmed and cleaned up
+    #[serde(default)]
+    pub transforms: Vec<Transform>,
+}
+
+/// Find a JS/JSON literal in a `<script>` and select from it like from a JSON response
+///
+/// Without `variable` or `regex` the whole script is parsed (JSON-LD, `__NEXT_DATA__`)
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize)]
+pub struct ScriptExtraction {
+    /// Name of the variable that holds the value, eg. `chapter_images` for `var chapter_images = [...]`
+    #[serde(default)]
+    pub variable: Option<String>,
+    /// Regex of which the `value` group (or else the first group) is the literal
+    #[serde(default, deserialize_with = "serde_regex::deserialize")]
+    pub regex: Option<Regex>,
+    /// JSON path applied to the parsed value
+    #[serde(default = "default_script_path")]
+    pub path: String,
+}
+
+fn default_script_path() -> String {
+    String::from("$")
 }
 
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -26,6 +62,16 @@ pub enum FixCapitalization {
     Skip,
 }
 
+impl FixCapitalization {
+    /// Name of the option in a config
+    pub fn name(&self) -> &'static str {
+        match self {
+            FixCapitalization::Title => "title",
+            FixCapitalization::Skip => "skip",
+        }
+    }
+}
+
 #[cfg_attr(feature = "debug", derive(Debug))]
 #[derive(Deserialize)]
 pub struct CleanupOption {
@@ -39,12 +85,25 @@ pub enum StringSelection {
     AllText { join_with: String },
     OwnText,
     Attributes(Vec<String>),
+    /// Select text with `from`, then keep only what the `value` group (or else the first group) of `regex` captured
+    Regex { regex: Regex, from: Box<StringSelection> },
+}
+
+impl StringSelection {
+    /// Keep the captured part of a text selected with [`StringSelection::Regex`]
+    pub fn capture(regex: &Regex, text: &str) -> String {
+        regex
+            .captures(text)
+            .and_then(|captures| captures.name("value").or_else(|| captures.get(1)).or_else(|| captures.get(0)))
+            .map(|capture| capture.as_str().to_string())
+            .unwrap_or_default()
+    }
 }
 
 impl Default for StringSelection {
     fn default() -> Self {
         Self::AllText {
-            join_with: String::from(" "),
+            join_with: String::from(DEFAULT_JOIN_WITH),
         }
     }
 }
@@ -70,6 +129,8 @@ impl<'de> Deserialize<'de> for StringSelection {
                 let mut text_type: Option<String> = None;
                 let mut join_with = None;
                 let mut attributes = None;
+                let mut regex: Option<String> = None;
+                let mut from = None;
 
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
@@ -91,8 +152,20 @@ impl<'de> Deserialize<'de> for StringSelection {
                             }
                             attributes = Some(map.next_value()?);
                         }
+                        "regex" => {
+                            if regex.is_some() {
+                                return Err(serde::de::Error::duplicate_field("regex"));
+                            }
+                            regex = Some(map.next_value()?);
+                        }
+                        "from" => {
+                            if from.is_some() {
+                                return Err(serde::de::Error::duplicate_field("from"));
+                            }
+                            from = Some(map.next_value()?);
+                        }
                         _ => {
-                            // Ignore unknown fields
+                            // Unknown fields are reported as warnings when the config is validated
                             let _ = map.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
@@ -100,10 +173,15 @@ impl<'de> Deserialize<'de> for StringSelection {
 
                 match text_type.as_deref() {
                     Some("own-text") => Ok(StringSelection::OwnText),
-                    Some("all-text") => Ok(StringSelection::AllText { join_with: join_with.unwrap_or(" ".to_string()) }),
+                    Some("all-text") => Ok(StringSelection::AllText { join_with: join_with.unwrap_or(DEFAULT_JOIN_WITH.to_string()) }),
                     Some("attributes") => Ok(StringSelection::Attributes(
                         attributes.unwrap_or_default(),
                     )),
+                    Some("regex") => Ok(StringSelection::Regex {
+                        regex: Regex::new(&regex.ok_or_else(|| serde::de::Error::missing_field("regex"))?)
+                            .map_err(serde::de::Error::custom)?,
+                        from: Box::new(from.unwrap_or_default()),
+                    }),
                     _ => Err(serde::de::Error::missing_field("type")),
                 }
             }
@@ -112,3 +190,129 @@ impl<'de> Deserialize<'de> for StringSelection {
         deserializer.deserialize_map(Visitor)
     }
 }
+
+impl ConfigSchema for StringSelectorOptions {
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "cleanup": generator.described::<Vec<CleanupOption>>("Cleanup text that has been scraped with regexps"),
+                "fix_capitalization": generator.subschema::<FixCapitalization>(),
+                "text_selection": generator.subschema::<StringSelection>(),
+                "script": generator.subschema::<Option<ScriptExtraction>>(),
+                "transforms": generator.described::<Vec<Transform>>(
+                    "Steps applied in order to the selected text, before it is trimmed and cleaned up"
+                ),
+            },
+        })
+    }
+}
+
+impl ConfigSchema for ScriptExtraction {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::STRING_SELECTOR_SCHEMA, "script"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        let mut variable = generator.described::<String>("Name of the variable that holds the value");
+        variable["examples"] = json!(["chapter_images"]);
+        let mut path = generator.described::<String>("JSON path applied to the parsed value");
+        path["default"] = json!(default_script_path());
+        path["examples"] = json!(["$[*]", "$.props.pageProps.series.title"]);
+
+        json!({
+            "description": "Parse a JS/JSON literal out of the selected script and select from it with a JSON path. \
+                Without variable or regex the whole script is parsed (JSON-LD, __NEXT_DATA__)",
+            "type": "object",
+            "properties": {
+                "variable": variable,
+                "regex": generator.described::<Regex>(
+                    "Regex of which the group named value (or else the first group) is the literal"
+                ),
+                "path": path,
+            },
+        })
+    }
+}
+
+impl ConfigSchema for FixCapitalization {
+    fn schema(_generator: &SchemaGenerator) -> Value {
+        json!({
+            "description": "Fix bad capitalization",
+            "default": FixCapitalization::default().name(),
+            "enum": [FixCapitalization::Title.name(), FixCapitalization::Skip.name()],
+        })
+    }
+}
+
+impl ConfigSchema for CleanupOption {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::STRING_SELECTOR_SCHEMA, "cleanup"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "type": "object",
+            "required": ["replace_regex", "replace_with"],
+            "properties": {
+                "replace_regex": generator.described::<Regex>("Replace what matches with this regex"),
+                "replace_with": generator.described::<String>("Replace matched parts with this string"),
+            },
+        })
+    }
+}
+
+impl ConfigSchema for StringSelection {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::STRING_SELECTOR_SCHEMA, "text_selection"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        let mut regex = generator.described::<Regex>(
+            "Regex applied to the selected text, the part captured by the group named value (or else the first group) is kept",
+        );
+        regex["examples"] = json!([r"Chapter (?<value>\d+(\.\d+)?)"]);
+
+        json!({
+            "description": "Determine how text should be selected",
+            "oneOf": [
+                {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "const": "own-text" },
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "const": "all-text" },
+                        "join_with": {
+                            "type": "string",
+                            "description": "When more than one element is found, this determines how the strings should be joined",
+                            "default": DEFAULT_JOIN_WITH,
+                        },
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": { "const": "attributes" },
+                        "attributes": {
+                            "type": "array",
+                            "description": "Attributes to take the text from, the first one an element has is used",
+                            "items": { "type": "string", "examples": ["data-src", "src"] },
+                        },
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": ["type", "regex"],
+                    "properties": {
+                        "type": { "const": "regex" },
+                        "regex": regex,
+                        "from": generator.described::<StringSelection>(
+                            "How the text the regex is applied to is selected, defaults to all-text"
+                        ),
+                    },
+                },
+            ],
+        })
+    }
+}
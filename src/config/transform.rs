@@ -0,0 +1,233 @@
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine,
+};
+use regex::{Captures, Regex};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    error::ScrapeError,
+    util::json_path::{json_to_text, JsonPath},
+};
+
+use super::schema::{self, ConfigSchema, Location, SchemaGenerator};
+
+lazy_static::lazy_static! {
+    static ref HTML_ENTITY: Regex = Regex::new(r"&(?:#(?<decimal>\d+)|#[xX](?<hex>[0-9a-fA-F]+)|(?<name>[a-zA-Z]+));").unwrap();
+}
+
+/// Padding is optional, as plenty of websites leave it out
+const BASE64_CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const BASE64: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, BASE64_CONFIG);
+const BASE64_URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, BASE64_CONFIG);
+
+/// A step applied to the selected text, before it is trimmed and cleaned up
+///
+/// ```yaml
+/// transforms:
+///   - trim-prefix: "data:"
+///   - base64-decode
+///   - json: $.images[*]
+///   - absolute-url
+/// ```
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transform {
+    /// Standard or URL-safe base64, with or without padding
+    Base64Decode,
+    /// Percent-decoding, like `%2F` to `/`
+    UrlDecode,
+    /// HTML entities, like `&quot;` to `"`
+    HtmlUnescape,
+    /// Parse the text as JSON and select from it with a JSON path, every value it selects becomes a text of its own
+    Json(String),
+    /// Remove the prefix when the text starts with it, like `data:` before an encoded value
+    TrimPrefix(String),
+    /// Lowercase the whole text
+    Lowercase,
+    /// Resolve the text against the URL of the page it was selected from
+    AbsoluteUrl,
+}
+
+impl Transform {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Transform::Base64Decode => "base64-decode",
+            Transform::UrlDecode => "url-decode",
+            Transform::HtmlUnescape => "html-unescape",
+            Transform::Json(_) => "json",
+            Transform::TrimPrefix(_) => "trim-prefix",
+            Transform::Lowercase => "lowercase",
+            Transform::AbsoluteUrl => "absolute-url",
+        }
+    }
+
+    /// The texts this text turns into, `url` is the page the text was selected from
+    pub fn apply(&self, text: String, url: &Url) -> Result<Vec<String>, ScrapeError> {
+        let error =
+            |message: String| ScrapeError::WebScrapingError(format!("{} of {text:?} failed: {message}", self.name()));
+        let transformed = match self {
+            Transform::Base64Decode => {
+                let encoded = text.trim();
+                let bytes = BASE64
+                    .decode(encoded)
+                    .or_else(|_| BASE64_URL_SAFE.decode(encoded))
+                    .map_err(|e| error(e.to_string()))?;
+                String::from_utf8(bytes).map_err(|e| error(e.to_string()))?
+            }
+            Transform::UrlDecode => percent_encoding::percent_decode_str(&text)
+                .decode_utf8()
+                .map_err(|e| error(e.to_string()))?
+                .into_owned(),
+            Transform::HtmlUnescape => unescape_html(&text),
+            Transform::Json(path) => {
+                let path = JsonPath::parse(path).map_err(error)?;
+                let value: Value = serde_json::from_str(&text).map_err(|e| error(e.to_string()))?;
+                return Ok(path
+                    .select(&value)
+                    .into_iter()
+                    .flat_map(|value| match value {
+                        // Selecting an array is the same as selecting its items
+                        Value::Array(items) => items.iter().map(json_to_text).collect(),
+                        value => vec![json_to_text(value)],
+                    })
+                    .collect());
+            }
+            Transform::TrimPrefix(prefix) => text.strip_prefix(prefix.as_str()).unwrap_or(&text).to_string(),
+            Transform::Lowercase => text.to_lowercase(),
+            Transform::AbsoluteUrl => url.join(text.trim()).map_err(|e| error(e.to_string()))?.to_string(),
+        };
+        Ok(vec![transformed])
+    }
+}
+
+/// Apply every transform in turn
+pub fn apply_transforms(transforms: &[Transform], text: String, url: &Url) -> Result<Vec<String>, ScrapeError> {
+    let mut texts = vec![text];
+    for transform in transforms {
+        texts = texts
+            .into_iter()
+            .map(|text| transform.apply(text, url))
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+    }
+    Ok(texts)
+}
+
+/// Replace numeric character references and the common named entities
+fn unescape_html(text: &str) -> String {
+    HTML_ENTITY
+        .replace_all(text, |captures: &Captures| {
+            let character = if let Some(decimal) = captures.name("decimal") {
+                decimal.as_str().parse().ok().and_then(char::from_u32)
+            } else if let Some(hex) = captures.name("hex") {
+                u32::from_str_radix(hex.as_str(), 16).ok().and_then(char::from_u32)
+            } else {
+                match &captures["name"] {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some('\u{a0}'),
+                    _ => None,
+                }
+            };
+            character.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+impl ConfigSchema for Transform {
+    const LOCATION: Option<Location> = Some(Location::definition(schema::STRING_SELECTOR_SCHEMA, "transform"));
+
+    fn schema(generator: &SchemaGenerator) -> Value {
+        json!({
+            "oneOf": [
+                {
+                    "enum": [
+                        Transform::Base64Decode.name(),
+                        Transform::UrlDecode.name(),
+                        Transform::HtmlUnescape.name(),
+                        Transform::Lowercase.name(),
+                        Transform::AbsoluteUrl.name(),
+                    ],
+                },
+                {
+                    "type": "object",
+                    "required": [Transform::Json(String::new()).name()],
+                    "properties": {
+                        "json": generator.described::<String>(
+                            "Parse the text as JSON and select from it with a JSON path, \
+                            every value it selects becomes a text of its own"
+                        ),
+                    },
+                },
+                {
+                    "type": "object",
+                    "required": [Transform::TrimPrefix(String::new()).name()],
+                    "properties": {
+                        "trim-prefix": generator.described::<String>("Remove this from the start of the text"),
+                    },
+                },
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use config::{builder::DefaultState, ConfigBuilder, File, FileFormat};
+    use reqwest::Url;
+    use serde::Deserialize;
+
+    use super::{apply_transforms, Transform};
+
+    #[derive(Deserialize)]
+    struct Transforms {
+        transforms: Vec<Transform>,
+    }
+
+    fn transforms(yaml: &str) -> Vec<Transform> {
+        ConfigBuilder::<DefaultState>::default()
+            .add_source(File::from_str(yaml, FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize::<Transforms>()
+            .unwrap()
+            .transforms
+    }
+
+    #[test]
+    fn test_transforms() {
+        let url = Url::parse("https://example.com/manga/1/chapter-2").unwrap();
+        let apply = |yaml: &str, text: &str| apply_transforms(&transforms(yaml), text.to_string(), &url).unwrap();
+
+        // {"images":["/p/1.jpg","//cdn.example.com/p/2.jpg"]}
+        let encoded = "data:eyJpbWFnZXMiOlsiL3AvMS5qcGciLCIvL2Nkbi5leGFtcGxlLmNvbS9wLzIuanBnIl19";
+        assert_eq!(
+            apply(
+                "transforms: [{ trim-prefix: 'data:' }, base64-decode, { json: '$.images[*]' }, absolute-url]",
+                encoded
+            ),
+            vec!["https://example.com/p/1.jpg", "https://cdn.example.com/p/2.jpg"]
+        );
+        assert_eq!(
+            apply(
+                "transforms: [html-unescape, { json: $.title }, lowercase]",
+                "{&quot;title&quot;:&quot;Caf&#233; &amp; Bar&quot;}"
+            ),
+            vec!["café & bar"]
+        );
+        assert_eq!(apply("transforms: [url-decode]", "a%2Fb%20c"), vec!["a/b c"]);
+        // URL-safe without padding
+        assert_eq!(apply("transforms: [base64-decode]", "Pz8-"), vec!["??>"]);
+
+        assert!(apply_transforms(&transforms("transforms: [base64-decode]"), "%%".to_string(), &url).is_err());
+    }
+}
@@ -0,0 +1,633 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use reqwest::header::{HeaderName, HeaderValue};
+use serde_json::Value;
+
+use crate::util::json_path::JsonPath;
+
+use super::{
+    loader,
+    schema::{self, CONFIG_SCHEMA},
+    MangaScraperConfig,
+};
+
+lazy_static::lazy_static! {
+    static ref SCHEMAS: Schemas = Schemas::new();
+}
+
+/// A problem found in a config file
+///
+/// `path` points at the offending value the way it is written in YAML, eg. `manga.chapter.fetch_external[0].regex`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub file: PathBuf,
+    pub path: String,
+    pub message: String,
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    /// The config does not load
+    Error,
+    /// The config loads, but probably not the way it was meant to, like with a misspelled key that is ignored
+    Warning,
+}
+
+impl ConfigIssue {
+    pub fn new(file: &Path, path: &str, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            path: path.to_string(),
+            message: message.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    pub fn warning(file: &Path, path: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(file, path, message)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.file.display())?;
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        if self.severity == Severity::Warning {
+            write!(f, "warning: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Every problem in the configs of a directory, warnings included, empty when they all load cleanly
+pub fn lint_dir(path: &Path) -> Vec<ConfigIssue> {
+    match loader::check_sources(&[loader::ConfigSource::Dir(path.to_path_buf())]) {
+        Ok((_, issues)) => issues,
+        Err(error) => vec![ConfigIssue::new(path, "", error.to_string())],
+    }
+}
+
+/// Check a config (with `extends` already resolved) against `schema/config.schema.yaml`
+/// and make sure its selectors and regexes compile
+///
+/// Selectors starting with `$` are checked as JSON paths, anything else as a CSS selector.
+/// Keys the schema does not know are warnings, as they are ignored when the config is loaded
+pub fn validate_config(file: &Path, config: &Value) -> Vec<ConfigIssue> {
+    let mut issues = Issues::default();
+    SCHEMAS.validate(CONFIG_SCHEMA, &SCHEMAS.documents[CONFIG_SCHEMA], config, "", &mut issues);
+    check_config(config, &mut issues);
+
+    let errors = issues
+        .errors
+        .into_iter()
+        .map(|(path, message)| ConfigIssue::new(file, &path, message));
+    let warnings = issues
+        .unknown_keys
+        .into_iter()
+        .map(|(path, key)| ConfigIssue::warning(file, &path, format!("unknown key {key} is ignored")));
+    errors.chain(warnings).collect()
+}
+
+/// Hostnames accepted by more than one config, the configs are tried by priority so this is only a warning
+pub fn duplicate_hostnames(configs: &[(PathBuf, MangaScraperConfig)]) -> Vec<ConfigIssue> {
+    let mut seen: HashMap<&str, &str> = HashMap::new();
+    let mut issues = vec![];
+    for (file, config) in configs {
+        for (index, hostname) in config.accept.hostnames.iter().enumerate() {
+            match seen.get(hostname.as_str()) {
+                Some(other) => issues.push(ConfigIssue::warning(
+                    file,
+                    &format!("accept.hostnames[{index}]"),
+                    format!("hostname {hostname} is also accepted by config {other}, they are tried by priority"),
+                )),
+                None => {
+                    seen.insert(hostname, &config.name);
+                }
+            }
+        }
+    }
+    issues
+}
+
+#[derive(Default)]
+struct Issues {
+    errors: Vec<(String, String)>,
+    /// Path and name of keys that are not in the schema
+    unknown_keys: Vec<(String, String)>,
+}
+
+impl Issues {
+    fn push(&mut self, path: &str, message: impl Into<String>) {
+        self.errors.push((path.to_string(), message.into()));
+    }
+
+    fn extend(&mut self, other: Issues) {
+        self.errors.extend(other.errors);
+        self.unknown_keys.extend(other.unknown_keys);
+    }
+}
+
+fn key_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn index_path(path: &str, index: usize) -> String {
+    format!("{path}[{index}]")
+}
+
+/// The schemas in `schema/`, generated once and looked up by `$id`
+pub(super) struct Schemas {
+    documents: HashMap<&'static str, Value>,
+}
+
+impl Schemas {
+    pub(super) fn new() -> Self {
+        Self {
+            documents: schema::schema_documents().into_iter().collect(),
+        }
+    }
+
+    /// Follow a `$ref` like `string_selector.schema.yaml#/$defs/cleanup` or `#/$defs/manga`
+    fn resolve<'a>(&'a self, document: &'a str, reference: &str) -> Option<(&'a str, &'a Value)> {
+        let (file, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        let (document, mut schema) = if file.is_empty() {
+            self.documents.get_key_value(document)?
+        } else {
+            self.documents.get_key_value(file)?
+        };
+        for segment in fragment.split('/').filter(|segment| !segment.is_empty()) {
+            schema = schema.get(segment)?;
+        }
+        Some((document, schema))
+    }
+
+    /// The schema a `$ref` points to, or the schema itself when it is not a reference
+    pub(super) fn follow<'a>(&'a self, document: &'a str, schema: &'a Value) -> (&'a str, &'a Value) {
+        match schema.get("$ref").and_then(Value::as_str) {
+            Some(reference) => self
+                .resolve(document, reference)
+                .map(|(document, schema)| self.follow(document, schema))
+                .unwrap_or((document, schema)),
+            None => (document, schema),
+        }
+    }
+
+    fn is_valid(&self, document: &str, schema: &Value, value: &Value) -> bool {
+        let mut issues = Issues::default();
+        self.validate(document, schema, value, "", &mut issues);
+        issues.errors.is_empty()
+    }
+
+    /// The subset of JSON schema the schemas in `schema/` use
+    fn validate(&self, document: &str, schema: &Value, value: &Value, path: &str, issues: &mut Issues) {
+        let Some(keywords) = schema.as_object() else {
+            return;
+        };
+
+        if let Some(reference) = keywords.get("$ref").and_then(Value::as_str) {
+            match self.resolve(document, reference) {
+                Some((document, schema)) => self.validate(document, schema, value, path, issues),
+                None => issues.push(path, format!("unknown schema reference {reference}")),
+            }
+        }
+
+        if let Some(types) = keywords.get("type") {
+            let types = schema_types(types);
+            if !types.iter().any(|expected| has_type(value, expected)) {
+                issues.push(path, format!("expected {}, found {}", types.join(" or "), type_name(value)));
+                return;
+            }
+        }
+
+        if let Some(constant) = keywords.get("const") {
+            if value != constant {
+                issues.push(path, format!("expected {constant}, found {value}"));
+            }
+        }
+
+        if let Some(Value::Array(allowed)) = keywords.get("enum") {
+            if !allowed.contains(value) {
+                issues.push(path, format!("expected one of {}, found {value}", join_values(allowed)));
+            }
+        }
+
+        match value {
+            Value::Object(object) => {
+                if let Some(Value::Array(required)) = keywords.get("required") {
+                    for key in required.iter().filter_map(Value::as_str) {
+                        if !object.contains_key(key) {
+                            issues.push(path, format!("missing required key {key}"));
+                        }
+                    }
+                }
+                let properties = keywords.get("properties").and_then(Value::as_object);
+                for (key, value) in object {
+                    match properties
+                        .and_then(|properties| properties.get(key))
+                        .or_else(|| keywords.get("additionalProperties"))
+                    {
+                        Some(schema) => self.validate(document, schema, value, &key_path(path, key), issues),
+                        None if properties.is_some() => {
+                            issues.unknown_keys.push((key_path(path, key), key.clone()));
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(min_items) = keywords.get("minItems").and_then(Value::as_u64) {
+                    if (items.len() as u64) < min_items {
+                        issues.push(path, format!("expected at least {min_items} items"));
+                    }
+                }
+                if keywords.get("uniqueItems").and_then(Value::as_bool) == Some(true) {
+                    for (index, item) in items.iter().enumerate() {
+                        if items[..index].contains(item) {
+                            issues.push(&index_path(path, index), format!("duplicate item {item}"));
+                        }
+                    }
+                }
+                if let Some(schema) = keywords.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.validate(document, schema, item, &index_path(path, index), issues);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(condition) = keywords.get("if") {
+            let branch = if self.is_valid(document, condition, value) {
+                keywords.get("then")
+            } else {
+                keywords.get("else")
+            };
+            if let Some(branch) = branch {
+                self.validate(document, branch, value, path, issues);
+            }
+        }
+
+        if let Some(not) = keywords.get("not") {
+            if self.is_valid(document, not, value) {
+                issues.push(path, "matches a form that is not allowed");
+            }
+        }
+
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(Value::Array(branches)) = keywords.get(keyword) {
+                self.validate_branches(document, branches, value, path, issues);
+            }
+        }
+    }
+
+    /// Validate against the first matching branch of a `oneOf`/`anyOf`
+    ///
+    /// When nothing matches, the branches are narrowed down by type and by `const` properties (like `type: own-text`)
+    /// so the reported issues are the ones of the form that was most likely meant
+    fn validate_branches(&self, document: &str, branches: &[Value], value: &Value, path: &str, issues: &mut Issues) {
+        let mut results = vec![];
+        for branch in branches {
+            let mut branch_issues = Issues::default();
+            self.validate(document, branch, value, path, &mut branch_issues);
+            if branch_issues.errors.is_empty() {
+                issues.extend(branch_issues);
+                return;
+            }
+            results.push((self.follow(document, branch).1, branch_issues));
+        }
+
+        results.retain(|(schema, _)| {
+            schema
+                .get("type")
+                .map(|types| schema_types(types).iter().any(|expected| has_type(value, expected)))
+                .unwrap_or(true)
+        });
+
+        if let Value::Object(object) = value {
+            for (key, given) in object {
+                let constants: Vec<&Value> = results
+                    .iter()
+                    .filter_map(|(schema, _)| schema.get("properties")?.get(key)?.get("const"))
+                    .collect();
+                if constants.is_empty() {
+                    continue;
+                }
+                if !constants.contains(&given) {
+                    issues.push(
+                        &key_path(path, key),
+                        format!("unknown {key} {given}, expected one of {}", join_values(constants)),
+                    );
+                    return;
+                }
+                results.retain(|(schema, _)| {
+                    schema
+                        .get("properties")
+                        .and_then(|properties| properties.get(key))
+                        .and_then(|property| property.get("const"))
+                        .is_some_and(|constant| constant == given)
+                });
+            }
+        }
+
+        match results.len() {
+            1 => issues.extend(results.pop().unwrap().1),
+            _ => issues.push(path, format!("{} does not match any of the allowed forms", type_name(value))),
+        }
+    }
+}
+
+fn schema_types(types: &Value) -> Vec<&str> {
+    match types {
+        Value::String(expected) => vec![expected.as_str()],
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        expected => type_name(value) == expected,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join_values<'a>(values: impl IntoIterator<Item = &'a Value>) -> String {
+    values.into_iter().map(Value::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Checks the schema can not express, every selector and regex has to compile
+fn check_config(config: &Value, issues: &mut Issues) {
+    if let Some(Value::Array(selectors)) = config.pointer("/accept/selectors") {
+        for (index, selector) in selectors.iter().enumerate() {
+            if let Value::String(selector) = selector {
+                check_selector(selector, &index_path("accept.selectors", index), issues);
+            }
+        }
+    }
+
+    if let Some(manga) = config.get("manga") {
+        let keys = [
+            "title",
+            "description",
+            "cover_url",
+            "status",
+            "authors",
+            "artists",
+            "genres",
+            "content_rating",
+            "publication_type",
+            "year",
+            "original_language",
+            "alt_titles",
+        ];
+        for key in keys {
+            check_selectors(manga.get(key), &key_path("manga", key), issues);
+        }
+        if let Some(Value::Object(status_map)) = manga.get("status_map") {
+            for (status, matches) in status_map {
+                if let Value::Array(matches) = matches {
+                    let path = key_path("manga.status_map", status);
+                    for (index, status_match) in matches.iter().enumerate() {
+                        check_regex(status_match.get("regex"), &key_path(&index_path(&path, index), "regex"), issues);
+                    }
+                }
+            }
+        }
+        if let Some(chapter) = manga.get("chapter") {
+            for key in ["base", "title", "number", "date", "url"] {
+                check_selectors(chapter.get(key), &key_path("manga.chapter", key), issues);
+            }
+            for key in ["number_regex", "volume_regex"] {
+                check_regex(chapter.get(key), &key_path("manga.chapter", key), issues);
+            }
+            check_fetch_external(chapter.get("fetch_external"), "manga.chapter.fetch_external", issues);
+            check_pagination(chapter.get("pagination"), "manga.chapter.pagination", issues);
+        }
+    }
+
+    if let Some(images) = config.get("images") {
+        check_selectors(images.get("image_selector"), "images.image_selector", issues);
+        check_fetch_external(images.get("fetch_external"), "images.fetch_external", issues);
+    }
+
+    if let Some(image_urls) = config.get("image_urls") {
+        check_cleanup(image_urls.get("rewrite"), "image_urls.rewrite", issues);
+    }
+
+    if let Some(Value::Array(searches)) = config.get("search") {
+        for (index, search) in searches.iter().enumerate() {
+            let path = index_path("search", index);
+            check_cleanup(search.get("query_format"), &key_path(&path, "query_format"), issues);
+            check_pagination(search.get("pagination"), &key_path(&path, "pagination"), issues);
+            check_headers(search.get("headers"), &key_path(&path, "headers"), issues);
+            if let Some(selectors) = search.get("selectors") {
+                for key in ["base", "url", "title", "cover_url", "posted"] {
+                    check_selectors(selectors.get(key), &key_path(&path, &format!("selectors.{key}")), issues);
+                }
+            }
+        }
+    }
+
+    if let Some(http) = config.get("http") {
+        check_headers(http.get("headers"), "http.headers", issues);
+    }
+}
+
+/// A string or array selector, as a single string, a selector object or a list of either
+fn check_selectors(selectors: Option<&Value>, path: &str, issues: &mut Issues) {
+    match selectors {
+        Some(Value::String(selector)) => check_selector(selector, path, issues),
+        Some(Value::Object(selector)) => {
+            if let Some(Value::String(query)) = selector.get("selector") {
+                check_selector(query, &key_path(path, "selector"), issues);
+            }
+            if let Some(options) = selector.get("options") {
+                check_options(options, &key_path(path, "options"), issues);
+            }
+        }
+        Some(Value::Array(selectors)) => {
+            for (index, selector) in selectors.iter().enumerate() {
+                check_selectors(Some(selector), &index_path(path, index), issues);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_selector(selector: &str, path: &str, issues: &mut Issues) {
+    if selector.trim_start().starts_with('$') {
+        if let Err(e) = JsonPath::parse(selector) {
+            issues.push(path, format!("invalid JSON path: {e}"));
+        }
+    } else if kuchiki::Selectors::compile(selector).is_err() {
+        issues.push(path, format!("invalid CSS selector {selector}"));
+    }
+}
+
+fn check_options(options: &Value, path: &str, issues: &mut Issues) {
+    check_cleanup(options.get("cleanup"), &key_path(path, "cleanup"), issues);
+    check_regex(options.get("text_split_regex"), &key_path(path, "text_split_regex"), issues);
+    check_text_selection(options.get("text_selection"), &key_path(path, "text_selection"), issues);
+    if let Some(Value::Array(transforms)) = options.get("transforms") {
+        for (index, transform) in transforms.iter().enumerate() {
+            if let Some(Value::String(json_path)) = transform.get("json") {
+                if let Err(e) = JsonPath::parse(json_path) {
+                    let path = key_path(&index_path(&key_path(path, "transforms"), index), "json");
+                    issues.push(&path, format!("invalid JSON path: {e}"));
+                }
+            }
+        }
+    }
+    if let Some(script) = options.get("script") {
+        let path = key_path(path, "script");
+        check_regex(script.get("regex"), &key_path(&path, "regex"), issues);
+        if let Some(Value::String(json_path)) = script.get("path") {
+            if let Err(e) = JsonPath::parse(json_path) {
+                issues.push(&key_path(&path, "path"), format!("invalid JSON path: {e}"));
+            }
+        }
+    }
+}
+
+fn check_text_selection(text_selection: Option<&Value>, path: &str, issues: &mut Issues) {
+    if let Some(text_selection) = text_selection {
+        check_regex(text_selection.get("regex"), &key_path(path, "regex"), issues);
+        check_text_selection(text_selection.get("from"), &key_path(path, "from"), issues);
+    }
+}
+
+fn check_cleanup(cleanup: Option<&Value>, path: &str, issues: &mut Issues) {
+    if let Some(Value::Array(cleanup)) = cleanup {
+        for (index, option) in cleanup.iter().enumerate() {
+            check_regex(option.get("replace_regex"), &key_path(&index_path(path, index), "replace_regex"), issues);
+        }
+    }
+}
+
+fn check_fetch_external(fetch_external: Option<&Value>, path: &str, issues: &mut Issues) {
+    let Some(Value::Array(fetch_external)) = fetch_external else {
+        return;
+    };
+    for (index, external) in fetch_external.iter().enumerate() {
+        let path = index_path(path, index);
+        check_selectors(external.get("id"), &key_path(&path, "id"), issues);
+        if let Some(regex) = check_regex(external.get("regex"), &key_path(&path, "regex"), issues) {
+            if !regex.capture_names().flatten().any(|name| name == "id") {
+                issues.push(&key_path(&path, "regex"), "regex has no group named id");
+            }
+        }
+        check_headers(external.get("headers"), &key_path(&path, "headers"), issues);
+    }
+}
+
+fn check_headers(headers: Option<&Value>, path: &str, issues: &mut Issues) {
+    let Some(Value::Object(headers)) = headers else {
+        return;
+    };
+    for (name, value) in headers {
+        if HeaderName::from_bytes(name.as_bytes()).is_err() {
+            issues.push(path, format!("invalid header name {name}"));
+        } else if let Value::String(value) = value {
+            if HeaderValue::from_str(value).is_err() {
+                issues.push(&key_path(path, name), "invalid header value");
+            }
+        }
+    }
+}
+
+fn check_pagination(pagination: Option<&Value>, path: &str, issues: &mut Issues) {
+    let Some(pagination) = pagination else {
+        return;
+    };
+    check_selectors(pagination.get("next_page"), &key_path(path, "next_page"), issues);
+    if let Some(Value::String(selector)) = pagination.get("stop_selector") {
+        check_selector(selector, &key_path(path, "stop_selector"), issues);
+    }
+}
+
+fn check_regex(regex: Option<&Value>, path: &str, issues: &mut Issues) -> Option<Regex> {
+    let Some(Value::String(regex)) = regex else {
+        return None;
+    };
+    Regex::new(regex)
+        .map_err(|e| issues.push(path, format!("invalid regex: {e}")))
+        .ok()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{lint_dir, Severity};
+
+    #[test]
+    fn test_validate_configs() {
+        assert_eq!(lint_dir(Path::new("configs")), vec![]);
+    }
+
+    #[test]
+    fn test_validate_invalid_config() {
+        let issues = lint_dir(Path::new("tests/fragments/config/invalid"));
+        let issue = |path: &str| {
+            issues
+                .iter()
+                .find(|issue| issue.path == path)
+                .unwrap_or_else(|| panic!("no issue for {path} in {issues:#?}"))
+        };
+
+        assert!(issue("manga.title").message.contains("invalid CSS selector"));
+        assert!(issue("manga.description.selector").message.contains("invalid CSS selector"));
+        assert!(issue("manga.artists[1]").message.contains("invalid CSS selector"));
+        assert!(issue("manga.cover_url.options.text_selection.type").message.contains("unknown type \"src\""));
+        assert!(issue("manga.alt_titles[1].options.cleanup[0].replace_regex")
+            .message
+            .contains("invalid regex"));
+        assert!(issue("manga.status_map.hiatus[1].regex").message.contains("invalid regex"));
+        assert!(issue("manga").message.contains("missing required key chapter"));
+        assert!(issue("images.image_selector").message.contains("invalid JSON path"));
+        assert!(issue("images.fetch_external[0].regex").message.contains("no group named id"));
+        assert!(issue("http.headers").message.contains("invalid header name Bad Header"));
+        assert!(issue("http.referer").message.contains("\"previous\""));
+        let duplicate = issue("accept.hostnames[0]");
+        assert_eq!(duplicate.severity, Severity::Warning);
+        assert!(duplicate.message.contains("also accepted by config invalid-b"));
+        let unknown = issue("manga.alt_title");
+        assert_eq!(unknown.severity, Severity::Warning);
+        assert!(unknown.file.ends_with("c.yaml"));
+        assert!(issues
+            .iter()
+            .any(|issue| issue.file.ends_with("d.yaml") && issue.message.contains("Config version 99 is not supported")));
+        assert!(issues.iter().all(|issue| issue.file.starts_with("tests/fragments/config/invalid")));
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::ScrapeError;
+
+use super::{
+    loader::{self, ConfigSource},
+    MangaScraperConfig, SharedConfigs,
+};
+
+/// Watches the config directories and files of a set of sources
+/// and swaps in the new config list whenever a file changes
+///
+/// Files that fail to load are reported and keep their last good version
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    pub fn new(sources: Vec<ConfigSource>, configs: SharedConfigs) -> Result<Self, ScrapeError> {
+        let loaded = Mutex::new(HashMap::new());
+        // Seed with what is currently on disk so a broken file has something to fall back on
+        reload(&sources, &loaded, &configs);
+
+        let watch_paths: Vec<PathBuf> = sources
+            .iter()
+            .filter_map(|source| match source {
+                ConfigSource::Dir(path) | ConfigSource::File(path) => Some(path.clone()),
+                ConfigSource::Text { .. } => None,
+            })
+            .collect();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) && event.paths.iter().any(|path| is_config_path(path))
+                {
+                    debug!("[watcher] {:?} changed {:?}", event.kind, event.paths);
+                    reload(&sources, &loaded, &configs);
+                }
+            }
+            Err(e) => error!("[watcher] {e}"),
+        })?;
+        for path in &watch_paths {
+            watcher.watch(path, RecursiveMode::NonRecursive)?;
+        }
+
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+/// Removed files no longer exist, so only look at the extension
+fn is_config_path(path: &Path) -> bool {
+    loader::config_format(path).is_some()
+}
+
+fn reload(
+    sources: &[ConfigSource],
+    loaded: &Mutex<HashMap<PathBuf, Arc<MangaScraperConfig>>>,
+    configs: &SharedConfigs,
+) {
+    // Everything is reloaded together, so edits to a base config reach the configs extending it
+    let sources = match loader::load_config_sources(sources) {
+        Ok(sources) => sources,
+        Err(e) => {
+            error!("[watcher] could not read the configs: {e}");
+            return;
+        }
+    };
+
+    let mut loaded = loaded.lock().unwrap();
+    let mut next = HashMap::new();
+    for (file, config) in sources {
+        match config {
+            Ok(config) => {
+                next.insert(file, Arc::new(config));
+            }
+            Err(e) => {
+                error!("[watcher] could not load {}: {e}", file.display());
+                if let Some(previous) = loaded.remove(&file) {
+                    warn!("[watcher] keeping last good version of {}", file.display());
+                    next.insert(file, previous);
+                }
+            }
+        }
+    }
+    *loaded = next;
+
+    let mut new_configs: Vec<_> = loaded.values().cloned().collect();
+    new_configs.sort_by(|a, b| a.cmp_priority(b));
+
+    *configs.write().unwrap() = new_configs;
+    info!("[watcher] loaded {} config(s)", loaded.len());
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        path::Path,
+        sync::{Arc, RwLock},
+        time::{Duration, Instant},
+    };
+
+    use super::ConfigWatcher;
+    use crate::config::loader::ConfigSource;
+
+    fn wait_for(configs: &super::SharedConfigs, names: &[&str]) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            let mut current: Vec<String> = configs.read().unwrap().iter().map(|c| c.name.clone()).collect();
+            current.sort();
+            if current == names {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn test_watch_config_dir() {
+        let dir = std::env::temp_dir().join(format!("manga_parser_watch_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let madara = std::fs::read_to_string(Path::new("configs/madara.yaml")).unwrap();
+        std::fs::write(dir.join("madara.yaml"), &madara).unwrap();
+
+        let configs = Arc::new(RwLock::new(vec![]));
+        let _watcher = ConfigWatcher::new(vec![ConfigSource::Dir(dir.clone())], configs.clone()).unwrap();
+        assert!(wait_for(&configs, &["madara"]));
+
+        // Added
+        std::fs::write(dir.join("copy.yaml"), madara.replace("name: madara", "name: copy")).unwrap();
+        assert!(wait_for(&configs, &["copy", "madara"]));
+
+        // Broken edit keeps the last good version
+        std::fs::write(dir.join("copy.yaml"), "name: [").unwrap();
+        std::thread::sleep(Duration::from_millis(500));
+        assert!(wait_for(&configs, &["copy", "madara"]));
+
+        // Deleted
+        std::fs::remove_file(dir.join("copy.yaml")).unwrap();
+        assert!(wait_for(&configs, &["madara"]));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
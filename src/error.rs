@@ -1,7 +1,10 @@
 use std::{collections::HashMap, fmt::Display};
 
+use itertools::Itertools;
 use thiserror::Error;
 
+use crate::config::validate::ConfigIssue;
+
 #[derive(Error, Debug, strum::AsRefStr)]
 pub enum ScrapeError {
     #[error("Reqwest error: {0}")]
@@ -19,6 +22,25 @@ pub enum ScrapeError {
     #[error("Configuration error: {0}")]
     ConfigDeserializeError(String),
 
+    #[error("Config inheritance cycle: {}", .0.join(" -> "))]
+    ConfigInheritanceCycle(Vec<String>),
+
+    #[error("Config {0} extends unknown config {1}")]
+    UnknownBaseConfig(String, String),
+
+    #[error("Config version {0} is not supported, the supported versions are 1 to {1}")]
+    UnsupportedConfigVersion(i64, u32),
+
+    #[error("No config named {0}")]
+    UnknownConfig(String),
+
+    #[error("Invalid config:\n{}", .0.iter().join("\n"))]
+    InvalidConfig(Vec<ConfigIssue>),
+
+    #[cfg(feature = "watch_dir")]
+    #[error("Config watcher error: {0}")]
+    WatchError(#[from] notify::Error),
+
     #[error("Web scraping error: {0}")]
     WebScrapingError(String),
 
@@ -6,6 +6,7 @@ use chrono::{DateTime, Utc};
 pub struct Chapter {
     pub url: reqwest::Url,
     pub number: f32,
+    pub volume: Option<f32>,
     pub title: String,
     pub date: Option<DateTime<Utc>>,
 }
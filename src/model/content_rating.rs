@@ -0,0 +1,40 @@
+/// Audience a manga is suitable for, as MangaDex rates them
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub enum ContentRating {
+    Safe,
+    Suggestive,
+    Erotica,
+    Pornographic,
+    /// No rating found, or one that is not recognized
+    #[default]
+    Unknown,
+}
+
+/// Words websites use for each rating, the strictest ratings are checked first
+const RATING_WORDS: [(ContentRating, &[&str]); 4] = [
+    (
+        ContentRating::Pornographic,
+        &["pornographic", "porn", "hentai", "adult", "18+"],
+    ),
+    (ContentRating::Erotica, &["erotica", "erotic", "smut", "mature", "17+"]),
+    (
+        ContentRating::Suggestive,
+        &["suggestive", "ecchi", "teen", "16+", "13+"],
+    ),
+    (ContentRating::Safe, &["safe", "all ages", "everyone"]),
+];
+
+impl ContentRating {
+    /// The rating a text like "Mature (17+)" stands for
+    pub fn from_text(text: &str) -> Self {
+        let text = text.to_lowercase();
+        RATING_WORDS
+            .into_iter()
+            .find(|(_, words)| words.iter().any(|word| text.contains(word)))
+            .map(|(rating, _)| rating)
+            .unwrap_or_default()
+    }
+}
@@ -1,4 +1,4 @@
-use super::Chapter;
+use super::{Chapter, ContentRating, MangaSources, MangaStatus, PublicationType};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "debug", derive(Debug))]
@@ -8,13 +8,30 @@ pub struct Manga {
     pub title: String,
     pub description: String,
     pub cover_url: Option<reqwest::Url>,
-    pub status: Option<String>,
+    #[builder(default)]
+    pub status: MangaStatus,
+    /// Derived from the status, see [`MangaStatus::is_ongoing`], false when the page has no status
     pub is_ongoing: bool,
     #[builder(default)]
     pub authors: Vec<String>,
     #[builder(default)]
+    pub artists: Vec<String>,
+    #[builder(default)]
     pub genres: Vec<String>,
     #[builder(default)]
+    pub content_rating: ContentRating,
+    #[builder(default)]
+    pub publication_type: PublicationType,
+    /// Year the manga started
+    pub year: Option<u32>,
+    /// Language the manga was written in, as the source names it (`ja` for MangaDex, often "Japanese" elsewhere)
+    pub original_language: Option<String>,
+    #[builder(default)]
     pub alternative_titles: Vec<String>,
     pub chapters: Vec<Chapter>,
+    /// Scraper the manga comes from, `mangadex` or the name of the config that completed it
+    pub source_id: Option<String>,
+    /// Where the title, description, cover, authors and genres came from
+    #[builder(default)]
+    pub sources: MangaSources,
 }
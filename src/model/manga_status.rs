@@ -0,0 +1,21 @@
+/// Publication status of a manga
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub enum MangaStatus {
+    Ongoing,
+    Completed,
+    Hiatus,
+    Cancelled,
+    /// No status found, or one that is not recognized
+    #[default]
+    Unknown,
+}
+
+impl MangaStatus {
+    /// Whether new chapters can be expected, a status that is not recognized does not count as ongoing
+    pub fn is_ongoing(&self) -> bool {
+        matches!(self, MangaStatus::Ongoing)
+    }
+}
@@ -0,0 +1,28 @@
+/// Where a field of a manga came from
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum MetadataSource {
+    /// The selectors of the config
+    Config,
+    /// A schema.org object (`Book`, `ComicSeries` and the like) in a JSON-LD script
+    JsonLd,
+    /// Open Graph `og:` meta tags
+    OpenGraph,
+    /// The API of the website
+    Api,
+}
+
+/// Source of each manga field that falls back to the metadata of the page when the config finds nothing,
+/// `None` when no source had it
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Default)]
+pub struct MangaSources {
+    pub title: Option<MetadataSource>,
+    pub description: Option<MetadataSource>,
+    pub cover_url: Option<MetadataSource>,
+    pub authors: Option<MetadataSource>,
+    pub genres: Option<MetadataSource>,
+}
@@ -1,7 +1,17 @@
 mod chapter;
+mod content_rating;
 mod manga;
+mod manga_status;
+mod metadata_source;
+mod publication_type;
 mod search_manga;
+mod search_page;
 
 pub use chapter::*;
+pub use content_rating::*;
 pub use manga::*;
+pub use manga_status::*;
+pub use metadata_source::*;
+pub use publication_type::*;
 pub use search_manga::*;
+pub use search_page::*;
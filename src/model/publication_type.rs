@@ -0,0 +1,48 @@
+/// Kind of comic a manga is, which mostly follows from the country it comes from
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy, Default)]
+pub enum PublicationType {
+    /// Japanese
+    Manga,
+    /// Korean
+    Manhwa,
+    /// Chinese
+    Manhua,
+    /// Published as a vertical strip, whatever the country
+    Webtoon,
+    /// No type found, or one that is not recognized
+    #[default]
+    Unknown,
+}
+
+/// Words websites use for each type, webtoon first as webtoons are often labelled with their country too
+const TYPE_WORDS: [(PublicationType, &[&str]); 4] = [
+    (PublicationType::Webtoon, &["webtoon", "long strip"]),
+    (PublicationType::Manhwa, &["manhwa", "korean"]),
+    (PublicationType::Manhua, &["manhua", "chinese"]),
+    (PublicationType::Manga, &["manga", "japanese"]),
+];
+
+impl PublicationType {
+    /// The type a text like "Manhwa" or "Type: Korean" stands for
+    pub fn from_text(text: &str) -> Self {
+        let text = text.to_lowercase();
+        TYPE_WORDS
+            .into_iter()
+            .find(|(_, words)| words.iter().any(|word| text.contains(word)))
+            .map(|(publication_type, _)| publication_type)
+            .unwrap_or_default()
+    }
+
+    /// The type of a manga in its original language, an ISO 639-1 code like `ja` or `zh-hk`
+    pub fn from_language(language: &str) -> Self {
+        match language.split('-').next().unwrap_or_default() {
+            "ja" => PublicationType::Manga,
+            "ko" => PublicationType::Manhwa,
+            "zh" => PublicationType::Manhua,
+            _ => PublicationType::Unknown,
+        }
+    }
+}
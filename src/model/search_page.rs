@@ -0,0 +1,24 @@
+use super::SearchManga;
+
+/// One page of search results
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(PartialEq, Clone)]
+pub struct SearchPage {
+    /// Page number, starting at 1
+    pub page: u32,
+    pub results: Vec<SearchManga>,
+    /// Whether the next page may have more results
+    pub has_more: bool,
+}
+
+impl SearchPage {
+    /// A page without results, for pages past the last one
+    pub fn empty(page: u32) -> Self {
+        Self {
+            page,
+            results: vec![],
+            has_more: false,
+        }
+    }
+}
@@ -0,0 +1,354 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use http::Extensions;
+use reqwest::{header::RETRY_AFTER, Request, Response, StatusCode};
+use reqwest_middleware::{Middleware, Next};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::http::RateLimit;
+
+/// Longest `Retry-After` that is honoured
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+/// Hosts kept track of before idle hosts with the default limit are dropped
+const MAX_HOSTS: usize = 256;
+
+lazy_static::lazy_static! {
+    /// Rate limiter shared by every scraper
+    pub static ref RATE_LIMITER: RateLimiter = RateLimiter::default();
+}
+
+/// Token bucket per hostname with a cap on the requests in flight
+///
+/// Hosts nobody set a limit for get the default [`RateLimit`]
+#[derive(Default)]
+pub struct RateLimiter {
+    hosts: Mutex<HashMap<String, Arc<HostLimiter>>>,
+}
+
+struct HostLimiter {
+    in_flight: Arc<Semaphore>,
+    state: Mutex<HostState>,
+}
+
+struct HostState {
+    limit: RateLimit,
+    bucket: Bucket,
+    /// Permits to forget as requests finish, after `max_in_flight` was lowered while they were in flight
+    surplus: usize,
+}
+
+/// Permission to make a request, the request counts as in flight until this is dropped
+pub struct RatePermit {
+    permit: Option<OwnedSemaphorePermit>,
+    host: Arc<HostLimiter>,
+}
+
+impl RateLimiter {
+    /// Set the limit of a host, the requests in flight and the tokens left carry over
+    ///
+    /// The default limit is not set, so it does not loosen the limit another config set for the host
+    pub fn configure(&self, host: &str, limit: &RateLimit) {
+        if *limit != RateLimit::default() {
+            self.host(host).set_limit(limit);
+        }
+    }
+
+    /// Wait until a request to the host is allowed
+    pub async fn acquire(&self, host: &str) -> RatePermit {
+        let host = self.host(host);
+        let permit = host
+            .in_flight
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("the semaphore is never closed");
+        loop {
+            let wait = {
+                let mut state = host.state.lock().unwrap();
+                let HostState { limit, bucket, .. } = &mut *state;
+                bucket.take(limit, Instant::now())
+            };
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => break,
+            }
+        }
+        RatePermit {
+            permit: Some(permit),
+            host,
+        }
+    }
+
+    /// Hold off every request to the host for a while
+    pub fn retry_after(&self, host: &str, wait: Duration) {
+        let until = Instant::now() + wait.min(MAX_RETRY_AFTER);
+        let host = self.host(host);
+        let mut state = host.state.lock().unwrap();
+        state.bucket.blocked_until = state.bucket.blocked_until.max(Some(until));
+    }
+
+    fn host(&self, host: &str) -> Arc<HostLimiter> {
+        let mut hosts = self.hosts.lock().unwrap();
+        if let Some(limiter) = hosts.get(host) {
+            return limiter.clone();
+        }
+        if hosts.len() >= MAX_HOSTS {
+            // A new limiter for a dropped host starts out the same as the one it had
+            let now = Instant::now();
+            hosts.retain(|_, limiter| Arc::strong_count(limiter) > 1 || !limiter.is_idle(now));
+        }
+        hosts
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(HostLimiter::new(RateLimit::default())))
+            .clone()
+    }
+}
+
+impl HostLimiter {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            in_flight: Arc::new(Semaphore::new(limit.max_in_flight.max(1) as usize)),
+            state: Mutex::new(HostState {
+                bucket: Bucket::new(&limit, Instant::now()),
+                limit,
+                surplus: 0,
+            }),
+        }
+    }
+
+    fn set_limit(&self, limit: &RateLimit) {
+        let mut state = self.state.lock().unwrap();
+        if state.limit == *limit {
+            return;
+        }
+        let current = state.limit.max_in_flight.max(1) as usize;
+        let max_in_flight = limit.max_in_flight.max(1) as usize;
+        if max_in_flight > current {
+            let repaid = state.surplus.min(max_in_flight - current);
+            state.surplus -= repaid;
+            self.in_flight.add_permits(max_in_flight - current - repaid);
+        } else {
+            let forgotten = self.in_flight.forget_permits(current - max_in_flight);
+            state.surplus += current - max_in_flight - forgotten;
+        }
+        state.limit = limit.clone();
+    }
+
+    /// Whether the host has the default limit, no requests in flight and a full bucket
+    fn is_idle(&self, now: Instant) -> bool {
+        let state = self.state.lock().unwrap();
+        state.limit == RateLimit::default()
+            && self.in_flight.available_permits() == state.limit.max_in_flight.max(1) as usize
+            && state.bucket.is_full(&state.limit, now)
+    }
+}
+
+impl Drop for RatePermit {
+    fn drop(&mut self) {
+        let mut state = self.host.state.lock().unwrap();
+        if state.surplus > 0 {
+            state.surplus -= 1;
+            if let Some(permit) = self.permit.take() {
+                permit.forget();
+            }
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    blocked_until: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(limit.burst.max(1)),
+            updated: now,
+            blocked_until: None,
+        }
+    }
+
+    /// Whether the bucket has refilled and nothing asked to wait, so it is as good as a new one
+    fn is_full(&self, limit: &RateLimit, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.blocked_until.is_none_or(|blocked_until| blocked_until <= now)
+            && (limit.requests_per_second <= 0.0
+                || self.tokens + elapsed * limit.requests_per_second >= f64::from(limit.burst.max(1)))
+    }
+
+    /// Take a token, or how long to wait before trying again
+    fn take(&mut self, limit: &RateLimit, now: Instant) -> Option<Duration> {
+        if let Some(blocked_until) = self.blocked_until.filter(|blocked_until| *blocked_until > now) {
+            return Some(blocked_until - now);
+        }
+        if limit.requests_per_second <= 0.0 {
+            return None;
+        }
+
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.requests_per_second).min(f64::from(limit.burst.max(1)));
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / limit.requests_per_second))
+        }
+    }
+}
+
+/// How long a `Retry-After` header asks to wait, it is either a number of seconds or a date
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// Applies [`RATE_LIMITER`] to every request of a client
+///
+/// Goes after the retry middleware, so that retries wait for their turn and for the `Retry-After` of a
+/// `429 Too Many Requests` or `503 Service Unavailable`
+pub struct RateLimitMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let Some(host) = req.url().host_str().map(String::from) else {
+            return next.run(req, extensions).await;
+        };
+        let _permit = RATE_LIMITER.acquire(&host).await;
+        let response = next.run(req, extensions).await?;
+
+        if matches!(
+            response.status(),
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        ) {
+            if let Some(wait) = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, Utc::now()))
+            {
+                warn!("[rate limit] {host} asked to wait {}s", wait.as_secs());
+                RATE_LIMITER.retry_after(&host, wait);
+            }
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use chrono::{TimeZone, Utc};
+    use futures::executor::block_on;
+
+    use super::{parse_retry_after, Bucket, RateLimiter, MAX_HOSTS};
+    use crate::config::http::RateLimit;
+
+    #[test]
+    fn test_bucket() {
+        let limit = RateLimit {
+            requests_per_second: 2.0,
+            burst: 2,
+            max_in_flight: 1,
+        };
+        let start = Instant::now();
+        let mut bucket = Bucket::new(&limit, start);
+        assert_eq!(bucket.take(&limit, start), None);
+        assert_eq!(bucket.take(&limit, start), None);
+        assert_eq!(bucket.take(&limit, start), Some(Duration::from_millis(500)));
+        assert_eq!(bucket.take(&limit, start + Duration::from_millis(500)), None);
+
+        // The burst refills, but not beyond its size
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.take(&limit, later), None);
+        assert_eq!(bucket.take(&limit, later), None);
+        assert!(bucket.take(&limit, later).is_some());
+
+        bucket.blocked_until = Some(later + Duration::from_secs(30));
+        assert_eq!(
+            bucket.take(&limit, later + Duration::from_secs(20)),
+            Some(Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn test_configure() {
+        let limiter = RateLimiter::default();
+        let limit = RateLimit {
+            requests_per_second: 1.0,
+            burst: 2,
+            max_in_flight: 2,
+        };
+        limiter.configure("example.com", &limit);
+        let first = block_on(limiter.acquire("example.com"));
+        let second = block_on(limiter.acquire("example.com"));
+        // The default limit does not loosen the configured one
+        limiter.configure("example.com", &RateLimit::default());
+        let lower = RateLimit {
+            burst: 1,
+            max_in_flight: 1,
+            ..limit
+        };
+        limiter.configure("example.com", &lower);
+
+        // The requests in flight and the used up burst carry over
+        let host = limiter.host("example.com");
+        assert!(host.state.lock().unwrap().bucket.tokens < 1.0);
+        assert_eq!(host.in_flight.available_permits(), 0);
+        drop(first);
+        assert_eq!(host.in_flight.available_permits(), 0);
+        drop(second);
+        assert_eq!(host.in_flight.available_permits(), 1);
+    }
+
+    #[test]
+    fn test_max_hosts() {
+        let limiter = RateLimiter::default();
+        let limit = RateLimit {
+            requests_per_second: 1.0,
+            burst: 1,
+            max_in_flight: 1,
+        };
+        limiter.configure("example.com", &limit);
+        let _permit = block_on(limiter.acquire("busy.example.com"));
+        for index in 0..MAX_HOSTS {
+            limiter.host(&format!("{index}.cdn.example.com"));
+        }
+
+        // Only idle hosts with the default limit are dropped
+        let hosts = limiter.hosts.lock().unwrap();
+        assert!(hosts.len() <= MAX_HOSTS);
+        assert!(hosts.contains_key("example.com"));
+        assert!(hosts.contains_key("busy.example.com"));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 27, 0).unwrap();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:26:00 GMT", now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
@@ -0,0 +1,263 @@
+use kuchiki::{traits::TendrilSink, NodeRef};
+use serde_json::Value;
+
+use crate::{
+    config::string_selector_options::{ScriptExtraction, StringSelection},
+    error::ScrapeError,
+    util::{
+        json_path::{json_to_text, JsonPath},
+        kuchiki_elements::ElementsTrait,
+        script,
+    },
+};
+
+/// A fetched document, either parsed HTML or a JSON response
+///
+/// Selectors are CSS selectors for HTML and JSON paths (see [`JsonPath`]) for JSON
+#[derive(Clone)]
+pub enum DocWrapper {
+    Html(NodeRef),
+    Json(Value),
+}
+unsafe impl Send for DocWrapper {}
+unsafe impl Sync for DocWrapper {}
+
+impl DocWrapper {
+    pub fn from_html(html: &str) -> Result<Self, ScrapeError> {
+        let doc = std::panic::catch_unwind(|| kuchiki::parse_html().one(html))
+            .map_err(|_e| ScrapeError::WebScrapingError("Could not parse HTML".to_string()))?;
+        Ok(DocWrapper::Html(doc))
+    }
+
+    /// Parse a response body as JSON when the content type says so or when it looks like JSON, otherwise as HTML
+    pub fn from_body(body: &str, content_type: Option<&str>) -> Result<Self, ScrapeError> {
+        if content_type.is_some_and(|content_type| content_type.contains("json")) {
+            return serde_json::from_str(body)
+                .map(DocWrapper::Json)
+                .map_err(|e| ScrapeError::WebScrapingError(format!("Could not parse JSON: {e}")));
+        }
+
+        let trimmed = body.trim_start();
+        if trimmed.starts_with(['{', '[']) {
+            if let Ok(json) = serde_json::from_str(trimmed) {
+                return Ok(DocWrapper::Json(json));
+            }
+        }
+
+        Self::from_html(body)
+    }
+
+    /// Every element matching the selector, each as its own document
+    pub fn select_all(&self, selector: &str) -> Result<Vec<DocWrapper>, ScrapeError> {
+        match self {
+            DocWrapper::Html(node) => Ok(node
+                .select(selector)
+                .map_err(|_| ScrapeError::SelectorError(selector.to_string()))?
+                .map(|element| DocWrapper::Html(element.as_node().clone()))
+                .collect()),
+            DocWrapper::Json(json) => Ok(json_path(selector)?
+                .select(json)
+                .into_iter()
+                .map(|value| DocWrapper::Json(value.clone()))
+                .collect()),
+        }
+    }
+
+    /// Check if anything matches the selector
+    pub fn matches(&self, selector: &str) -> bool {
+        match self {
+            DocWrapper::Html(node) => node.select_first(selector).is_ok(),
+            DocWrapper::Json(json) => JsonPath::parse(selector).is_ok_and(|path| !path.select(json).is_empty()),
+        }
+    }
+
+    /// The value the first matching script assigns (see [`ScriptExtraction`]), as a JSON document
+    pub fn select_script(&self, selector: &str, extraction: &ScriptExtraction) -> Result<Option<DocWrapper>, ScrapeError> {
+        for text in self.select_texts(selector, &StringSelection::OwnText)? {
+            let literal = if let Some(variable) = &extraction.variable {
+                script::extract_variable(&text, variable)
+            } else if let Some(regex) = &extraction.regex {
+                regex
+                    .captures(&text)
+                    .and_then(|captures| captures.name("value").or_else(|| captures.get(1)))
+                    .and_then(|capture| script::extract_literal(capture.as_str()))
+            } else {
+                Some(text.as_str())
+            };
+
+            if let Some(value) = literal.and_then(script::parse_literal) {
+                return Ok(Some(DocWrapper::Json(value)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Text of everything matching the selector combined into one string
+    pub fn select_text(&self, selector: &str, text_selection: &StringSelection) -> Result<String, ScrapeError> {
+        match self {
+            DocWrapper::Html(node) => {
+                let elements = node
+                    .select(selector)
+                    .map_err(|_| ScrapeError::SelectorError(selector.to_string()))?;
+
+                Ok(html_text(&elements, text_selection))
+            }
+            DocWrapper::Json(json) => {
+                let mut texts = json_path(selector)?
+                    .select(json)
+                    .into_iter()
+                    .map(|value| json_text(value, text_selection));
+
+                Ok(match text_selection {
+                    StringSelection::AllText { join_with } => texts.collect::<Vec<_>>().join(join_with),
+                    _ => texts.find(|text| !text.is_empty()).unwrap_or_default(),
+                })
+            }
+        }
+    }
+
+    /// Text of every element matching the selector
+    pub fn select_texts(&self, selector: &str, text_selection: &StringSelection) -> Result<Vec<String>, ScrapeError> {
+        match self {
+            DocWrapper::Html(node) => Ok(node
+                .select(selector)
+                .map_err(|_| ScrapeError::SelectorError(selector.to_string()))?
+                .map(|element| html_text(element.as_node(), text_selection))
+                .collect()),
+            DocWrapper::Json(json) => Ok(json_path(selector)?
+                .select(json)
+                .into_iter()
+                .flat_map(|value| match value {
+                    // Selecting an array is the same as selecting its items
+                    Value::Array(items) => items.iter().map(|item| json_text(item, text_selection)).collect(),
+                    value => vec![json_text(value, text_selection)],
+                })
+                .collect()),
+        }
+    }
+}
+
+fn html_text<T: ElementsTrait>(elements: &T, text_selection: &StringSelection) -> String {
+    match text_selection {
+        StringSelection::AllText { join_with } => elements.all_text(join_with),
+        StringSelection::OwnText => elements.own_text(),
+        StringSelection::Attributes(attrs) => elements.attr_first_of(attrs).unwrap_or_default(),
+        StringSelection::Regex { regex, from } => StringSelection::capture(regex, &html_text(elements, from)),
+    }
+}
+
+fn json_path(selector: &str) -> Result<JsonPath, ScrapeError> {
+    JsonPath::parse(selector).map_err(ScrapeError::SelectorError)
+}
+
+/// Text of a JSON value, `attributes` pick the first key that exists on an object
+fn json_text(value: &Value, text_selection: &StringSelection) -> String {
+    match text_selection {
+        StringSelection::AllText { join_with } => match value {
+            Value::Array(items) => items.iter().map(json_to_text).collect::<Vec<_>>().join(join_with),
+            value => json_to_text(value),
+        },
+        StringSelection::OwnText => json_to_text(value),
+        StringSelection::Attributes(attrs) => attrs
+            .iter()
+            .find_map(|attr| value.get(attr))
+            .map(json_to_text)
+            .unwrap_or_default(),
+        StringSelection::Regex { regex, from } => StringSelection::capture(regex, &json_text(value, from)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DocWrapper;
+    use crate::config::string_selector_options::{ScriptExtraction, StringSelection};
+
+    #[test]
+    fn test_json_document() {
+        let doc = DocWrapper::from_body(
+            r#"{"chapters": [{"name": "Chapter 2", "slug": "/c/2"}, {"name": "Chapter 1", "slug": "/c/1"}], "images": ["a.jpg", "b.jpg"]}"#,
+            None,
+        )
+        .unwrap();
+        assert!(matches!(doc, DocWrapper::Json(_)));
+        assert!(doc.matches("$.chapters"));
+        assert!(!doc.matches("$.volumes"));
+
+        let chapters = doc.select_all("$.chapters[*]").unwrap();
+        assert_eq!(chapters.len(), 2);
+        let slug = StringSelection::Attributes(vec!["url".to_string(), "slug".to_string()]);
+        assert_eq!(chapters[0].select_text("$", &slug).unwrap(), "/c/2");
+        assert_eq!(chapters[1].select_text("name", &StringSelection::OwnText).unwrap(), "Chapter 1");
+
+        let images = doc.select_texts("$.images", &StringSelection::default()).unwrap();
+        assert_eq!(images, vec!["a.jpg", "b.jpg"]);
+        assert_eq!(doc.select_text("$.images", &StringSelection::default()).unwrap(), "a.jpg b.jpg");
+    }
+
+    #[test]
+    fn test_html_document() {
+        let doc = DocWrapper::from_body("<ul><li>[1] first</li><li>second</li></ul>", Some("text/html")).unwrap();
+        assert!(matches!(doc, DocWrapper::Html(_)));
+        assert_eq!(doc.select_all("li").unwrap().len(), 2);
+        assert_eq!(
+            doc.select_texts("li", &StringSelection::OwnText).unwrap(),
+            vec!["[1] first", "second"]
+        );
+    }
+
+    #[test]
+    fn test_script_selection() {
+        let doc = DocWrapper::from_html(
+            r#"<script type="application/ld+json">{"@type": "Book", "name": "Solo Leveling"}</script>
+            <script>var chapter_id = 5; var chapter_images = ['/1.jpg', '/2.jpg'];</script>"#,
+        )
+        .unwrap();
+
+        let images = ScriptExtraction {
+            variable: Some("chapter_images".to_string()),
+            regex: None,
+            path: "$[*]".to_string(),
+        };
+        let json = doc.select_script("script:not([type])", &images).unwrap().unwrap();
+        assert_eq!(
+            json.select_texts(&images.path, &StringSelection::default()).unwrap(),
+            vec!["/1.jpg", "/2.jpg"]
+        );
+
+        let id = ScriptExtraction {
+            variable: None,
+            regex: Some(regex::Regex::new(r"chapter_id = (\d+)").unwrap()),
+            path: "$".to_string(),
+        };
+        let json = doc.select_script("script", &id).unwrap().unwrap();
+        assert_eq!(json.select_text(&id.path, &StringSelection::OwnText).unwrap(), "5");
+
+        let ld_json = ScriptExtraction {
+            variable: None,
+            regex: None,
+            path: "$.name".to_string(),
+        };
+        let json = doc.select_script("script[type='application/ld+json']", &ld_json).unwrap().unwrap();
+        assert_eq!(json.select_text(&ld_json.path, &StringSelection::OwnText).unwrap(), "Solo Leveling");
+    }
+
+    #[test]
+    fn test_regex_selection() {
+        let doc = DocWrapper::from_html(
+            r#"<a href="/manga/solo-leveling/chapter-12">Vol.2 Chapter 12.5</a><a href="/manga/solo-leveling/chapter-13">Vol.2 Chapter 13</a>"#,
+        )
+        .unwrap();
+        let number = StringSelection::Regex {
+            regex: regex::Regex::new(r"Chapter (?<value>[\d.]+)").unwrap(),
+            from: Box::default(),
+        };
+        assert_eq!(doc.select_text("a", &number).unwrap(), "12.5");
+        assert_eq!(doc.select_texts("a", &number).unwrap(), vec!["12.5", "13"]);
+
+        let slug = StringSelection::Regex {
+            regex: regex::Regex::new(r"/manga/([^/]+)/").unwrap(),
+            from: Box::new(StringSelection::Attributes(vec!["href".to_string()])),
+        };
+        assert_eq!(doc.select_text("a", &slug).unwrap(), "solo-leveling");
+    }
+}
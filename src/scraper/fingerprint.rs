@@ -0,0 +1,179 @@
+use std::cmp::Ordering;
+
+use reqwest::Url;
+
+use crate::config::accept::Theme;
+
+use super::document::DocWrapper;
+
+/// Themes below this confidence are not used to pick configs
+pub const MIN_CONFIDENCE: f32 = 0.6;
+
+/// Something a page of the theme has, with how sure it makes us of the theme on its own
+struct Signal {
+    selector: &'static str,
+    weight: f32,
+}
+
+const fn signal(selector: &'static str, weight: f32) -> Signal {
+    Signal { selector, weight }
+}
+
+/// Signals of each theme, the assets of the theme are the strongest as they are on every page
+const SIGNATURES: [(Theme, &[Signal]); 3] = [
+    (
+        Theme::Madara,
+        &[
+            signal("script#madara-core-js", 0.9),
+            signal("[href*='/themes/madara'], [src*='/themes/madara']", 0.9),
+            signal("body.wp-manga-template-default, body.wp-manga-page", 0.7),
+            signal("li.wp-manga-chapter", 0.6),
+            signal("div.reading-content div.page-break", 0.6),
+            signal("div.summary__content, div.summary_image", 0.4),
+            signal("div.c-page-content", 0.3),
+        ],
+    ),
+    (
+        Theme::MangaStream,
+        &[
+            signal("[href*='/themes/mangareader'], [src*='/themes/mangareader']", 0.9),
+            signal("[href*='/themes/mangastream'], [src*='/themes/mangastream']", 0.9),
+            signal("div#chapterlist li[data-num]", 0.7),
+            signal("div#readerarea", 0.6),
+            signal("div.seriestucontl, div.infox", 0.4),
+            signal("div.bixbox", 0.3),
+        ],
+    ),
+    (
+        Theme::FoolSlide,
+        &[
+            signal("meta[name='generator'][content*='FoOlSlide']", 0.9),
+            signal("[href*='foolslide'], [src*='foolslide']", 0.8),
+            signal("div.comic.info div.thumbnail", 0.6),
+            signal("div.list div.group div.element div.title a[href*='/read/']", 0.6),
+            signal("div#page div.inner img.open", 0.6),
+        ],
+    ),
+];
+
+/// A theme a page looks like it was made with
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct ThemeMatch {
+    pub theme: Theme,
+    /// From 0 to 1, the signals combine as independent evidence
+    pub confidence: f32,
+    /// Selectors of the signals found on the page
+    pub signals: Vec<&'static str>,
+}
+
+/// Themes the page matches, the most likely first
+pub fn fingerprint(doc: &DocWrapper) -> Vec<ThemeMatch> {
+    let DocWrapper::Html(_) = doc else {
+        return vec![];
+    };
+    let mut matches: Vec<_> = SIGNATURES
+        .iter()
+        .filter_map(|(theme, signature)| {
+            let found: Vec<_> = signature.iter().filter(|signal| doc.matches(signal.selector)).collect();
+            if found.is_empty() {
+                return None;
+            }
+            let doubt: f32 = found.iter().map(|signal| 1.0 - signal.weight).product();
+            Some(ThemeMatch {
+                theme: *theme,
+                confidence: 1.0 - doubt,
+                signals: found.iter().map(|signal| signal.selector).collect(),
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(Ordering::Equal));
+    matches
+}
+
+/// Why a config was picked for a page
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub enum AcceptReason {
+    /// The hostname is one of `accept.hostnames`
+    Hostname,
+    /// One of `accept.selectors` matched
+    Selector,
+    /// The page was fingerprinted as one of `accept.themes`
+    Theme(Theme),
+}
+
+/// A config that will be tried on a page
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct ConfigCandidate {
+    pub config: String,
+    pub reason: AcceptReason,
+    /// 1 for hostnames and selectors, the confidence of the theme otherwise
+    pub confidence: f32,
+}
+
+/// What was found out about a page, see [`crate::scraper::generic::GenericScraper::detect`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone)]
+pub struct Detection {
+    /// The page after redirects
+    pub url: Url,
+    pub themes: Vec<ThemeMatch>,
+    /// The configs in the order they are tried
+    pub candidates: Vec<ConfigCandidate>,
+}
+
+impl Detection {
+    /// The most likely theme, when it is likely enough
+    pub fn theme(&self) -> Option<&ThemeMatch> {
+        self.themes.first().filter(|theme| theme.confidence >= MIN_CONFIDENCE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{fingerprint, MIN_CONFIDENCE};
+    use crate::{config::accept::Theme, scraper::document::DocWrapper};
+
+    #[test]
+    fn test_fingerprint() {
+        let madara = DocWrapper::from_html(&std::fs::read_to_string("tests/fragments/madara.html").unwrap()).unwrap();
+        let themes = fingerprint(&madara);
+        assert_eq!(themes[0].theme, Theme::Madara);
+        assert!(themes[0].confidence >= MIN_CONFIDENCE);
+
+        let mangastream = DocWrapper::from_html(
+            r#"<html><head>
+            <link rel="stylesheet" href="https://example.com/wp-content/themes/mangareader/style.css">
+            </head><body><div class="bixbox"><div id="chapterlist"><ul>
+                <li data-num="2"><a href="/chapter-2/">Chapter 2</a></li>
+            </ul></div></div></body></html>"#,
+        )
+        .unwrap();
+        let themes = fingerprint(&mangastream);
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].theme, Theme::MangaStream);
+        assert!(themes[0].confidence > 0.9);
+        assert_eq!(themes[0].signals.len(), 3);
+
+        let foolslide = DocWrapper::from_html(
+            r#"<html><body><div class="panel"><div class="comic info"><div class="thumbnail"><img src="/thumb.jpg"></div></div>
+            <div class="list"><div class="group"><div class="element"><div class="title">
+                <a href="https://example.com/read/manga/en/0/1/">Chapter 1</a>
+            </div></div></div></div></div></body></html>"#,
+        )
+        .unwrap();
+        let themes = fingerprint(&foolslide);
+        assert_eq!(themes[0].theme, Theme::FoolSlide);
+        assert!(themes[0].confidence >= MIN_CONFIDENCE);
+
+        let unknown = DocWrapper::from_html("<html><body><h1>Manga</h1></body></html>").unwrap();
+        assert!(fingerprint(&unknown).is_empty());
+    }
+}
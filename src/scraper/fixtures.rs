@@ -0,0 +1,134 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use reqwest::Url;
+
+use crate::{config::config_test::ConfigTest, error::ScrapeError};
+
+use super::{document::DocWrapper, generic::GenericScraper};
+
+tokio::task_local! {
+    /// Files served instead of fetching their URL, set while a config test runs
+    static FIXTURES: HashMap<Url, PathBuf>;
+}
+
+/// The document of a URL while a config test runs, `None` when there is no test running
+pub(super) fn fixture(url: &Url) -> Option<Result<(DocWrapper, Url), ScrapeError>> {
+    FIXTURES
+        .try_with(|fixtures| match fixtures.get(url) {
+            Some(path) => read_fixture(path).map(|doc| (doc, url.clone())),
+            None => Err(ScrapeError::WebScrapingError(format!("No fixture for {url}"))),
+        })
+        .ok()
+}
+
+/// Whether a URL can be loaded while a config test runs, `None` when there is no test running
+///
+/// Only URLs with a fixture are, so images stay on their host unless the test gives a mirror a fixture
+pub(super) fn available(url: &Url) -> Option<bool> {
+    FIXTURES.try_with(|fixtures| fixtures.contains_key(url)).ok()
+}
+
+fn read_fixture(path: &Path) -> Result<DocWrapper, ScrapeError> {
+    let body = std::fs::read_to_string(path)?;
+    let content_type = (path.extension().unwrap_or_default() == "json").then_some("application/json");
+    DocWrapper::from_body(&body, content_type)
+}
+
+/// A config test that did not go as expected
+#[derive(Debug)]
+pub struct ConfigTestFailure {
+    pub config: String,
+    pub url: String,
+    pub message: String,
+}
+
+impl Display for ConfigTestFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.config, self.url, self.message)
+    }
+}
+
+impl GenericScraper {
+    /// Run the tests of every config (see [`ConfigTest`]) against their fixtures, nothing is fetched
+    pub async fn run_config_tests(&self) -> Vec<ConfigTestFailure> {
+        let mut failures = vec![];
+        for config in self.configs() {
+            for test in &config.tests {
+                let messages = self
+                    .run_config_test(&config.name, test)
+                    .await
+                    .unwrap_or_else(|e| vec![e.to_string()]);
+                failures.extend(messages.into_iter().map(|message| ConfigTestFailure {
+                    config: config.name.clone(),
+                    url: test.url.clone(),
+                    message,
+                }));
+            }
+        }
+        failures
+    }
+
+    /// Every expectation of the test that is not met
+    async fn run_config_test(&self, name: &str, test: &ConfigTest) -> Result<Vec<String>, ScrapeError> {
+        let url = Url::parse(&test.url).map_err(|e| ScrapeError::NotAValidURL(e.to_string()))?;
+        let mut fixtures = HashMap::from([(url.clone(), PathBuf::from(&test.fixture))]);
+        for page in &test.pages {
+            let page_url = url
+                .join(&page.url)
+                .map_err(|e| ScrapeError::NotAValidURL(e.to_string()))?;
+            fixtures.insert(page_url, PathBuf::from(&page.fixture));
+        }
+
+        FIXTURES
+            .scope(fixtures, async {
+                let manga = self.manga_with_config(&url, name).await?;
+                let expect = &test.expect;
+                let mut mismatches = vec![];
+
+                if let Some(title) = expect.title.as_ref().filter(|title| **title != manga.title) {
+                    mismatches.push(format!("title is {:?}, expected {title:?}", manga.title));
+                }
+                if let Some(chapters) = expect.chapters.filter(|chapters| *chapters != manga.chapters.len()) {
+                    mismatches.push(format!("found {} chapters, expected {chapters}", manga.chapters.len()));
+                }
+
+                let first_chapter = manga.chapters.first();
+                if let Some(expected) = &expect.first_chapter {
+                    match first_chapter {
+                        Some(chapter) => {
+                            if let Some(number) = expected.number.filter(|number| *number != chapter.number) {
+                                mismatches.push(format!(
+                                    "first chapter is number {}, expected {number}",
+                                    chapter.number
+                                ));
+                            }
+                            let date = chapter.date.map(|date| date.date_naive());
+                            if let Some(expected_date) = expected.date.filter(|expected_date| Some(*expected_date) != date) {
+                                mismatches.push(format!("first chapter is from {date:?}, expected {expected_date}"));
+                            }
+                        }
+                        None => mismatches.push(String::from("no first chapter")),
+                    }
+                }
+
+                if let Some(images) = expect.images {
+                    match first_chapter {
+                        Some(chapter) => {
+                            let found = self.chapter_images_with_config(&chapter.url, name).await?.len();
+                            if found != images {
+                                mismatches.push(format!("found {found} images, expected {images}"));
+                            }
+                        }
+                        None => mismatches.push(String::from("no chapter to find images in")),
+                    }
+                }
+
+                Ok(mismatches)
+            })
+            .await
+    }
+}
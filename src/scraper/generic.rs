@@ -1,53 +1,159 @@
-use std::{collections::HashMap, ops::Deref, path::Path, time::Duration};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
 
 use chrono::{DateTime, Utc};
-use config::{builder::DefaultState, ConfigBuilder, File};
 use convert_case::Casing;
-use kuchiki::{traits::TendrilSink, NodeRef};
-use reqwest::{Body, Method, StatusCode, Url};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, ORIGIN, REFERER},
+    Method, StatusCode, Url,
+};
+use regex::Regex;
+use reqwest_middleware::ClientWithMiddleware;
 
 use crate::{
     config::{
         array_selector::ArraySelectors,
-        chapter::FetchExternal,
+        chapter::{self, FetchExternal, Pagination},
+        http::HttpConfig,
+        image_urls,
+        search::SearchConfig,
+        loader::{self, ConfigSource},
+        request::{self, RequestBody},
         string_selector::StringSelectors,
-        string_selector_options::{self, StringSelection},
-        MangaScraperConfig,
+        string_selector_options,
+        transform::apply_transforms,
+        MangaScraperConfig, SharedConfigs,
     },
     error::ScrapeError,
-    model::{Chapter, Manga, MangaBuilder, SearchManga},
-    util::kuchiki_elements::ElementsTrait,
-    HTTP_CLIENT,
+    model::{
+        Chapter, ContentRating, Manga, MangaBuilder, MangaSources, MangaStatus, MetadataSource,
+        PublicationType, SearchManga, SearchPage,
+    },
+    rate_limit::RATE_LIMITER,
+    util::number,
+    COOKIE_JAR, HTTP_CLIENT, HTTP_CLIENT_NO_REDIRECT,
 };
 
-use super::MangaScraper;
+use super::{
+    document::DocWrapper,
+    fingerprint::{fingerprint, AcceptReason, ConfigCandidate, Detection, ThemeMatch, MIN_CONFIDENCE},
+    fixtures,
+    metadata::PageMetadata,
+    MangaScraper,
+};
+
+lazy_static::lazy_static! {
+    static ref YEAR: Regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+}
 
 pub struct GenericScraper {
-    configs: Vec<MangaScraperConfig>,
+    sources: Vec<ConfigSource>,
+    configs: SharedConfigs,
+    #[cfg(feature = "watch_dir")]
+    watcher: Option<crate::config::watcher::ConfigWatcher>,
 }
 
 impl GenericScraper {
+    /// The built-in configs, overridden by the `configs` directory of the working directory when there is one
     pub fn new() -> Result<Self, ScrapeError> {
-        Self::new_with_config_path(Path::new("configs"))
+        Self::new_with_config_dirs(&[Path::new("configs")])
     }
 
+    /// Only the configs of one directory
     pub fn new_with_config_path(path: &Path) -> Result<Self, ScrapeError> {
-        let mut configs = vec![];
-
-        for file in path.read_dir()?.flatten() {
-            if matches!(
-                file.path().extension().unwrap_or_default().to_str().unwrap(),
-                "yaml" | "yml"
-            ) {
-                let config = ConfigBuilder::<DefaultState>::default()
-                    .add_source(File::from(file.path()))
-                    .build()?;
-                let manga_config = config.try_deserialize::<MangaScraperConfig>()?;
-
-                configs.push(manga_config);
+        Self::new_with_sources(vec![ConfigSource::Dir(path.to_path_buf())])
+    }
+
+    /// The built-in configs, overridden by the configs of each directory in turn, like built-in, system and user
+    ///
+    /// Directories that do not exist are skipped
+    pub fn new_with_config_dirs<P: AsRef<Path>>(dirs: &[P]) -> Result<Self, ScrapeError> {
+        let mut sources = ConfigSource::builtin();
+        for dir in dirs.iter().map(AsRef::as_ref) {
+            if dir.is_dir() {
+                sources.push(ConfigSource::Dir(dir.to_path_buf()));
+            } else {
+                debug!("[generic] no config directory at {}", dir.display());
             }
         }
-        Ok(Self { configs })
+        Self::new_with_sources(sources)
+    }
+
+    /// Only the configs in these YAML texts
+    pub fn new_with_config_texts(texts: &[&str]) -> Result<Self, ScrapeError> {
+        let sources = texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| ConfigSource::Text {
+                name: PathBuf::from(format!("<config {index}>")),
+                text: Cow::Owned(text.to_string()),
+            })
+            .collect();
+        Self::new_with_sources(sources)
+    }
+
+    /// The configs of these sources, later sources override configs of the same name in earlier ones
+    pub fn new_with_sources(sources: Vec<ConfigSource>) -> Result<Self, ScrapeError> {
+        let configs = loader::load_sources(&sources)?.into_iter().map(Arc::new).collect();
+
+        Ok(Self {
+            sources,
+            configs: Arc::new(RwLock::new(configs)),
+            #[cfg(feature = "watch_dir")]
+            watcher: None,
+        })
+    }
+
+    /// Reload configs whenever a file in a config directory is added, edited or deleted
+    ///
+    /// Configs that fail to load are logged and their last good version is kept
+    #[cfg(feature = "watch_dir")]
+    pub fn watch_config_dir(&mut self) -> Result<(), ScrapeError> {
+        if self.watcher.is_none() {
+            self.watcher = Some(crate::config::watcher::ConfigWatcher::new(
+                self.sources.clone(),
+                self.configs.clone(),
+            )?);
+        }
+        Ok(())
+    }
+
+    /// Snapshot of the currently loaded configs, in the order they are tried
+    pub fn configs(&self) -> Vec<Arc<MangaScraperConfig>> {
+        self.configs.read().unwrap().clone()
+    }
+
+    /// The loaded config with this name
+    pub fn config(&self, name: &str) -> Option<Arc<MangaScraperConfig>> {
+        self.configs().into_iter().find(|config| config.name == name)
+    }
+
+    /// Scrape a manga with the named config only, whether or not it accepts the URL
+    pub async fn manga_with_config(&self, url: &Url, name: &str) -> Result<Manga, ScrapeError> {
+        let config = self
+            .config(name)
+            .ok_or_else(|| ScrapeError::UnknownConfig(name.to_string()))?;
+        let (doc, url) = fetch_doc(url, &config.http, None).await?;
+
+        let mut manga_builder = MangaBuilder::new();
+        self.full_manga(url, &config, doc, &mut manga_builder, &mut MangaSources::default())
+            .await?
+            .build()
+            .map_err(|e| ScrapeError::WebScrapingError(e.to_string()))
+    }
+
+    /// Scrape the images of a chapter with the named config only, whether or not it accepts the URL
+    pub async fn chapter_images_with_config(&self, chapter_url: &Url, name: &str) -> Result<Vec<Url>, ScrapeError> {
+        let config = self
+            .config(name)
+            .ok_or_else(|| ScrapeError::UnknownConfig(name.to_string()))?;
+        let (doc, url) = fetch_doc(chapter_url, &config.http, None).await?;
+        self.images(url, &config, doc).await
     }
 
     fn select_required_url(
@@ -69,7 +175,7 @@ impl GenericScraper {
         selector: &StringSelectors,
         doc: DocWrapper,
     ) -> Result<Option<reqwest::Url>, ScrapeError> {
-        let url_string = self.select_string(selector, doc)?;
+        let url_string = self.select_string(url, selector, doc)?;
         if let Some(url_string) = url_string {
             Ok(Some(
                 url.join(&url_string)
@@ -80,28 +186,45 @@ impl GenericScraper {
         }
     }
 
-    fn select_required_string(&self, selector: &StringSelectors, doc: DocWrapper) -> Result<String, ScrapeError> {
-        self.select_string(selector, doc)?
+    fn select_required_string(
+        &self,
+        url: &Url,
+        selector: &StringSelectors,
+        doc: DocWrapper,
+    ) -> Result<String, ScrapeError> {
+        self.select_string(url, selector, doc)?
             .ok_or(ScrapeError::WebScrapingError(format!(
                 "Missing required field with selectors: {:?}",
                 selector.selectors
             )))
     }
 
-    fn select_string(&self, selectors: &StringSelectors, doc: DocWrapper) -> Result<Option<String>, ScrapeError> {
+    /// Text of the first selector that finds any, `url` is the page the document is from
+    fn select_string(
+        &self,
+        url: &Url,
+        selectors: &StringSelectors,
+        doc: DocWrapper,
+    ) -> Result<Option<String>, ScrapeError> {
         for selector in &selectors.selectors {
-            let elements = doc
-                .select(&selector.selector)
-                .map_err(|_| ScrapeError::SelectorError(selector.selector.clone()))?;
-
-            let mut text = match &selector.options.text_selection {
-                StringSelection::AllText { join_with } => elements.all_text(join_with),
-                StringSelection::OwnText => elements.own_text(),
-                StringSelection::Attributes(attrs) => {
-                    let first_attr = elements.attr_first_of(attrs).unwrap_or_default();
-                    first_attr
-                }
+            let mut text = match &selector.options.script {
+                Some(script) => match doc.select_script(&selector.selector, script)? {
+                    Some(json) => json.select_text(&script.path, &selector.options.text_selection)?,
+                    None => String::new(),
+                },
+                None => doc.select_text(&selector.selector, &selector.options.text_selection)?,
             };
+            if !text.is_empty() && !selector.options.transforms.is_empty() {
+                // A transform that makes several texts out of one, like json, keeps the first.
+                // One that fails counts as a miss, so the next selector is tried
+                text = match apply_transforms(&selector.options.transforms, text, url) {
+                    Ok(texts) => texts.into_iter().find(|text| !text.trim().is_empty()).unwrap_or_default(),
+                    Err(e) => {
+                        warn!("[transform] {} on {url}: {e}", selector.selector);
+                        String::new()
+                    }
+                };
+            }
 
             // Trim text
             text = text.trim().to_string();
@@ -129,22 +252,38 @@ impl GenericScraper {
         Ok(None)
     }
 
-    fn select_string_array(&self, selectors: &ArraySelectors, doc: DocWrapper) -> Result<Vec<String>, ScrapeError> {
+    /// Texts of the first selector that finds any, `url` is the page the document is from
+    fn select_string_array(
+        &self,
+        url: &Url,
+        selectors: &ArraySelectors,
+        doc: DocWrapper,
+    ) -> Result<Vec<String>, ScrapeError> {
         for selector in &selectors.selectors {
-            let elements = doc
-                .select(&selector.selector)
-                .map_err(|_| ScrapeError::SelectorError(selector.selector.clone()))?;
-
             let mut items = vec![];
 
-            for element in elements {
-                let element = element.as_node();
+            let mut texts = match &selector.options.script {
+                Some(script) => match doc.select_script(&selector.selector, script)? {
+                    Some(json) => json.select_texts(&script.path, &selector.options.text_selection)?,
+                    None => vec![],
+                },
+                None => doc.select_texts(&selector.selector, &selector.options.text_selection)?,
+            };
+            if !selector.options.transforms.is_empty() {
+                // Texts a transform fails on are left out
+                texts = texts
+                    .into_iter()
+                    .filter(|text| !text.is_empty())
+                    .filter_map(|text| {
+                        apply_transforms(&selector.options.transforms, text, url)
+                            .map_err(|e| warn!("[transform] {} on {url}: {e}", selector.selector))
+                            .ok()
+                    })
+                    .flatten()
+                    .collect();
+            }
 
-                let mut text = match &selector.options.text_selection {
-                    StringSelection::AllText { join_with } => element.all_text(join_with),
-                    StringSelection::OwnText => element.own_text(),
-                    StringSelection::Attributes(attrs) => element.attr_first_of(attrs).unwrap_or_default(),
-                };
+            for mut text in texts {
                 // Trim text
                 text = text.trim().to_string();
                 // Cleanup the text
@@ -179,12 +318,35 @@ impl GenericScraper {
         Ok(vec![])
     }
 
+    /// Select the elements a list (of chapters, search results) is built from
+    ///
+    /// The first selector that finds anything is used
+    fn select_base(&self, selectors: &StringSelectors, doc: &DocWrapper) -> Result<Vec<DocWrapper>, ScrapeError> {
+        let mut elements = None;
+        for selector in &selectors.selectors {
+            let selected = match &selector.options.script {
+                Some(script) => match doc.select_script(&selector.selector, script)? {
+                    Some(json) => json.select_all(&script.path)?,
+                    None => vec![],
+                },
+                None => doc.select_all(&selector.selector)?,
+            };
+            let found = !selected.is_empty();
+            elements = Some(selected);
+            if found {
+                break;
+            }
+        }
+        elements.ok_or(ScrapeError::SelectorError("No base selectors".to_string()))
+    }
+
     async fn do_search(
         &self,
         config: &MangaScraperConfig,
         hostname: &str,
         query: &str,
-    ) -> Result<Vec<SearchManga>, ScrapeError> {
+        page: u32,
+    ) -> Result<SearchPage, ScrapeError> {
         let search_config = &config.search;
         let search_config = search_config
             .iter()
@@ -204,65 +366,122 @@ impl GenericScraper {
                 .to_string();
         }
 
-        let mut search_url = search_config
-            .search_url
-            .replace("{hostname}", hostname)
-            .replace("{query}", &query);
+        let page_number = page.to_string();
+        let site = Url::parse(&format!("https://{hostname}/")).map_err(|e| ScrapeError::NotAValidURL(e.to_string()))?;
+        let mut variables = request::variables("", &site, &query);
+        variables.push(("page", &page_number));
+        let mut search_url = request::interpolate(&search_config.search_url, &variables);
         if !search_url.starts_with("http") {
             search_url = String::from("https://") + &search_url;
         }
         let search_url = Url::parse(&search_url).map_err(|e| ScrapeError::NotAValidURL(e.to_string()))?;
         debug!("[SEARCH]: Search URL is {}", search_url.to_string());
 
-        let (doc, ..) = fetch_doc_config(&search_url, Method::GET, None::<String>).await?;
+        let Some((doc, page_url)) = self
+            .search_doc(search_config, &config.http, search_url, page, &variables)
+            .await? else {
+            return Ok(SearchPage::empty(page));
+        };
+        let search_url = page_url;
 
-        let elements = {
-            let mut elements: Option<kuchiki::iter::Select<kuchiki::iter::Elements<kuchiki::iter::Descendants>>> = None;
-            for selector in &search_config.selectors.base.selectors {
-                elements = Some(
-                    doc.select(&selector.selector)
-                        .map_err(|_| ScrapeError::SelectorError("Error in search base selector".to_string()))?,
-                );
-                if elements.as_ref().is_some_and(|els| !els.is_empty()) {
-                    break;
-                }
-            }
-            elements.ok_or(ScrapeError::SelectorError("Error in search base selector".to_string()))
-        }?;
+        let elements = self
+            .select_base(&search_config.selectors.base, &doc)
+            .map_err(|_| ScrapeError::SelectorError("Error in search base selector".to_string()))?;
 
         let mut search_results = vec![];
 
         for element in elements {
             search_results.push(SearchManga {
-                url: self.select_required_url(
-                    &search_url,
-                    &search_config.selectors.url,
-                    DocWrapper(element.as_node().clone()),
-                )?,
-                title: self
-                    .select_required_string(&search_config.selectors.title, DocWrapper(element.as_node().clone()))?,
+                url: self.select_required_url(&search_url, &search_config.selectors.url, element.clone())?,
+                title: self.select_required_string(&search_url, &search_config.selectors.title, element.clone())?,
                 cover_url: search_config
                     .selectors
                     .cover_url
                     .as_ref()
-                    .and_then(|selector| {
-                        self.select_url(&search_url, selector, DocWrapper(element.as_node().clone()))
-                            .ok()
-                    })
-                    .flatten(),
+                    .and_then(|selector| self.select_url(&search_url, selector, element.clone()).ok())
+                    .flatten()
+                    .map(|cover_url| config.image_urls.rewrite(cover_url)),
                 posted: search_config
                     .selectors
                     .posted
                     .as_ref()
                     .and_then(|selector| {
-                        self.select_date(&config.date_formats, selector, DocWrapper(element.as_node().clone()))
+                        self.select_date(&search_url, &config.date_formats, selector, element.clone())
                             .ok()
                     })
                     .flatten(),
             })
         }
 
-        Ok(search_results)
+        let has_more = self.has_more_results(search_config, &doc, &search_url, page, &search_results);
+        Ok(SearchPage {
+            page,
+            results: search_results,
+            has_more,
+        })
+    }
+
+    /// Fetch a page of search results, `None` when the config can not get to that page
+    async fn search_doc(
+        &self,
+        search_config: &SearchConfig,
+        http: &HttpConfig,
+        search_url: Url,
+        page: u32,
+        variables: &[(&str, &str)],
+    ) -> Result<Option<(DocWrapper, Url)>, ScrapeError> {
+        if page <= 1 || search_config.is_paged() {
+            return fetch_search(search_config, http, &search_url, variables).await.map(Some);
+        }
+        let Some(pagination) = search_config.pagination.as_ref().filter(|pagination| page <= pagination.max_pages) else {
+            return Ok(None);
+        };
+        if let Some(page_url) = pagination.page_url(&search_url, page - 2).transpose()? {
+            return fetch_search(search_config, http, &page_url, variables).await.map(Some);
+        }
+        let Some(next_page) = &pagination.next_page else {
+            return Ok(None);
+        };
+
+        // Follow the links to the next page from the first page
+        let (mut doc, mut page_url) = fetch_search(search_config, http, &search_url, variables).await?;
+        for _ in 1..page {
+            let Some(next_url) = self.select_url(&page_url, next_page, doc.clone())? else {
+                return Ok(None);
+            };
+            (doc, page_url) = fetch_doc(&next_url, http, Some(&page_url)).await?;
+        }
+        Ok(Some((doc, page_url)))
+    }
+
+    fn has_more_results(
+        &self,
+        search_config: &SearchConfig,
+        doc: &DocWrapper,
+        page_url: &Url,
+        page: u32,
+        results: &[SearchManga],
+    ) -> bool {
+        if results.is_empty() {
+            return false;
+        }
+        let Some(pagination) = &search_config.pagination else {
+            return search_config.is_paged();
+        };
+        if page >= pagination.max_pages
+            || pagination
+                .stop_selector
+                .as_ref()
+                .is_some_and(|selector| doc.matches(selector))
+        {
+            return false;
+        }
+        match &pagination.next_page {
+            Some(next_page) => self
+                .select_url(page_url, next_page, doc.clone())
+                .is_ok_and(|next_url| next_url.is_some()),
+            None => true,
+        }
     }
 
     async fn fetch_external(
@@ -270,6 +489,7 @@ impl GenericScraper {
         url: &Url,
         mut doc: DocWrapper,
         fetch_external: &[FetchExternal],
+        http: &HttpConfig,
     ) -> Result<DocWrapper, ScrapeError> {
         for ext_fetch in fetch_external {
             let hostname = url.host_str().unwrap();
@@ -277,24 +497,27 @@ impl GenericScraper {
                 "[external] Trying fetch on {} with selectors ({:#?})",
                 hostname, ext_fetch.id.selectors
             );
-            let element = self.select_string(&ext_fetch.id, doc.clone()).ok();
+            let element = self.select_string(url, &ext_fetch.id, doc.clone()).ok();
             if let Some(Some(text)) = element {
                 debug!("[external] Found {:?}", text);
                 let id = ext_fetch.regex.captures(&text);
                 if let Some(id) = id.and_then(|id| id.name("id")) {
                     let id = id.as_str();
                     debug!("[external] Which is id {}", id);
-                    let chapter_url = ext_fetch.url.replace("{id}", id);
-                    let chapter_url = chapter_url.replace("{host}", hostname);
-                    let chapter_url = chapter_url.replace("{url}", url.as_str());
+                    let variables = request::variables(id, url, "");
+                    let chapter_url = request::interpolate(&ext_fetch.url, &variables);
                     debug!("[external] URL is {}", chapter_url);
-                    if let Ok(url) = url.join(&chapter_url) {
+                    if let Ok(chapter_url) = url.join(&chapter_url) {
                         debug!("[external] Full URL is {}", chapter_url);
-                        let method = match ext_fetch.method.as_str() {
-                            "post" => Method::POST,
-                            _ => Method::GET,
-                        };
-                        let (chapter_doc, ..) = fetch_doc_config(&url, method, None::<String>).await?;
+                        let (chapter_doc, ..) = fetch_doc_config(
+                            &chapter_url,
+                            http,
+                            Some(url),
+                            http_method(&ext_fetch.method),
+                            &request::render_headers(&ext_fetch.headers, &variables),
+                            ext_fetch.body.as_ref().map(|body| body.render(&variables)),
+                        )
+                        .await?;
                         doc = chapter_doc;
                         break;
                     }
@@ -313,70 +536,148 @@ impl GenericScraper {
     ) -> Result<Vec<Chapter>, ScrapeError> {
         let chapter_config = &config.manga.chapter;
 
-        let doc = self.fetch_external(url, doc, &chapter_config.fetch_external).await?;
+        let doc = self
+            .fetch_external(url, doc, &chapter_config.fetch_external, &config.http)
+            .await?;
 
-        let elements = {
-            let mut elements = None;
-            for selector in &chapter_config.base.selectors {
-                elements = Some(
-                    doc.select(&selector.selector)
-                        .map_err(|_| ScrapeError::SelectorError("Error in chapter base selector".to_string()))?,
-                );
-                if elements.as_ref().is_some_and(|els| !els.is_empty()) {
-                    break;
-                }
-            }
-            elements.ok_or(ScrapeError::SelectorError("Error in chapter base selector".to_string()))
-        }?;
+        let mut seen = HashSet::new();
+        let mut elements = self.chapter_page(url, &doc, chapter_config, &mut seen)?;
+        if let Some(pagination) = &chapter_config.pagination {
+            self.paginate_chapters(url, doc, config, pagination, &mut seen, &mut elements)
+                .await?;
+        }
 
         let mut chapters = vec![];
-        let total_chapters = elements.len();
-        for (index, element) in elements.enumerate() {
-            let title = self.select_required_string(&chapter_config.title, DocWrapper(element.as_node().clone()))?;
+        let mut numbers = vec![];
+        for (chapter_url, element) in elements {
+            let title = self.select_required_string(url, &chapter_config.title, element.clone())?;
             let number_text = chapter_config
                 .number
                 .as_ref()
                 .and_then(|selector| {
-                    self.select_string(selector, DocWrapper(element.as_node().clone()))
+                    self.select_string(url, selector, element.clone())
                         .ok()
                         .flatten()
                 })
                 .unwrap_or_else(|| title.clone());
+            let (number, volume) = chapter_config.number_and_volume(&number_text, &title);
+            numbers.push(number);
 
             chapters.push(Chapter {
-                url: self.select_required_url(url, &chapter_config.url, DocWrapper(element.as_node().clone()))?,
+                url: chapter_url,
                 title,
-                number: crate::util::number::try_parse_number(&number_text).unwrap_or((total_chapters - index) as f32),
+                // Set below, specials and unnumbered chapters are numbered after the chapter before them
+                number: 0.0,
+                volume,
                 date: chapter_config
                     .date
                     .as_ref()
                     .and_then(|selector| {
-                        self.select_date(&config.date_formats, selector, DocWrapper(element.as_node().clone()))
+                        self.select_date(url, &config.date_formats, selector, element.clone())
                             .ok()
                     })
                     .flatten(),
             })
         }
+        for (chapter, number) in chapters.iter_mut().zip(number::order_chapters(&numbers)) {
+            chapter.number = number;
+        }
+
+        Ok(chapters)
+    }
+
+    /// The chapter elements of one page with their URLs, skipping chapters that are already `seen`
+    fn chapter_page(
+        &self,
+        page_url: &Url,
+        doc: &DocWrapper,
+        chapter_config: &chapter::Chapter,
+        seen: &mut HashSet<Url>,
+    ) -> Result<Vec<(Url, DocWrapper)>, ScrapeError> {
+        let elements = self
+            .select_base(&chapter_config.base, doc)
+            .map_err(|_| ScrapeError::SelectorError("Error in chapter base selector".to_string()))?;
 
+        let mut chapters = vec![];
+        for element in elements {
+            let chapter_url = self.select_required_url(page_url, &chapter_config.url, element.clone())?;
+            if seen.insert(chapter_url.clone()) {
+                chapters.push((chapter_url, element));
+            }
+        }
         Ok(chapters)
     }
 
+    /// Add the chapters of the pages after the first one
+    ///
+    /// Stops at the first page that can not be fetched or has no new chapters
+    async fn paginate_chapters(
+        &self,
+        url: &Url,
+        mut doc: DocWrapper,
+        config: &MangaScraperConfig,
+        pagination: &Pagination,
+        seen: &mut HashSet<Url>,
+        elements: &mut Vec<(Url, DocWrapper)>,
+    ) -> Result<(), ScrapeError> {
+        let chapter_config = &config.manga.chapter;
+        let mut page_url = url.clone();
+        for index in 0..pagination.max_pages.saturating_sub(1) {
+            if pagination
+                .stop_selector
+                .as_ref()
+                .is_some_and(|selector| doc.matches(selector))
+            {
+                break;
+            }
+
+            let next_url = match &pagination.next_page {
+                Some(next_page) => self.select_url(&page_url, next_page, doc.clone())?,
+                None => pagination.page_url(url, index).transpose()?,
+            };
+            let Some(next_url) = next_url.filter(|next_url| *next_url != page_url) else {
+                break;
+            };
+
+            debug!("[chapters] fetching page {}", next_url.as_str());
+            let (next_doc, next_url) = match fetch_doc(&next_url, &config.http, Some(&page_url)).await {
+                Ok(page) => page,
+                Err(e) => {
+                    debug!("[chapters] stopping pagination at {}: {e}", next_url.as_str());
+                    break;
+                }
+            };
+
+            let mut chapters = self.chapter_page(&next_url, &next_doc, chapter_config, seen)?;
+            if chapters.is_empty() {
+                break;
+            }
+            elements.append(&mut chapters);
+            doc = next_doc;
+            page_url = next_url;
+        }
+        Ok(())
+    }
+
     fn select_date(
         &self,
+        url: &Url,
         date_formats: &[String],
         selector: &StringSelectors,
         doc: DocWrapper,
     ) -> Result<Option<DateTime<Utc>>, ScrapeError> {
-        let text = self.select_required_string(selector, doc)?;
+        let text = self.select_required_string(url, selector, doc)?;
         Ok(crate::util::date::try_parse_date(&text, date_formats))
     }
 
     async fn images(&self, url: Url, config: &MangaScraperConfig, doc: DocWrapper) -> Result<Vec<Url>, ScrapeError> {
         debug!("[images] parsing images for {}", url.as_str());
 
-        let doc = self.fetch_external(&url, doc, &config.images.fetch_external).await?;
+        let doc = self
+            .fetch_external(&url, doc, &config.images.fetch_external, &config.http)
+            .await?;
 
-        let images = self.select_string_array(&config.images.image_selector, doc)?;
+        let images = self.select_string_array(&url, &config.images.image_selector, doc)?;
         debug!("[images] found {} images", images.len());
 
         let images = images
@@ -389,15 +690,52 @@ impl GenericScraper {
 
         debug!("[images] parsed {} images to URLs", images.len());
 
-        Ok(images)
+        Ok(self.image_urls(images, config, &url).await)
+    }
+
+    /// Apply the rewrite rules of the config and move the images to the first mirror that has them
+    ///
+    /// Only the first image is checked, the other images on its host are assumed to be on the same mirrors
+    async fn image_urls(&self, images: Vec<Url>, config: &MangaScraperConfig, page: &Url) -> Vec<Url> {
+        let image_urls = &config.image_urls;
+        let images: Vec<Url> = images.into_iter().map(|image| image_urls.rewrite(image)).collect();
+        let Some(first) = images.first() else {
+            return images;
+        };
+        if image_urls.mirrors.is_empty() {
+            return images;
+        }
+
+        for candidate in image_urls.candidates(first) {
+            if !image_available(&candidate, &config.http, page).await {
+                continue;
+            }
+            let Some(host) = candidate.host_str().filter(|host| Some(*host) != first.host_str()) else {
+                return images;
+            };
+            info!("[images] {} is not available, using mirror {host}", first.host_str().unwrap_or_default());
+            return images
+                .iter()
+                .map(|image| {
+                    Some(image)
+                        .filter(|image| image.host_str() == first.host_str())
+                        .and_then(|image| image_urls::on_host(image, host))
+                        .unwrap_or_else(|| image.clone())
+                })
+                .collect();
+        }
+        warn!("[images] {first} is not available on any mirror");
+        images
     }
 
+    /// Fill in what the builder is missing, `sources` keeps track of where the fields came from across configs
     async fn full_manga<'a>(
         &self,
         url: Url,
         config: &MangaScraperConfig,
         doc: DocWrapper,
         manga_builder: &'a mut MangaBuilder,
+        sources: &mut MangaSources,
     ) -> Result<&'a mut MangaBuilder, ScrapeError> {
         debug!("[manga] parsing manga at {}", url.as_str());
 
@@ -405,107 +743,240 @@ impl GenericScraper {
         manga_builder.url(url.clone());
         // Status
         if !manga_builder.has_status() {
-            if let Some(status) = config
+            let status = config
                 .manga
                 .status
                 .as_ref()
-                .map_or(Ok(None), |selector| self.select_string(selector, doc.clone()))?
-            {
-                manga_builder.status(status.clone());
-                manga_builder.is_ongoing(self.manga_status(Some(status)));
-            }
+                .map_or(Ok(None), |selector| self.select_string(&url, selector, doc.clone()))?
+                .map_or(MangaStatus::Unknown, |status| config.manga.status_map.status(&status));
+            manga_builder.status(status);
+            manga_builder.is_ongoing(status.is_ongoing());
         }
+        // Title, description, cover, authors and genres fall back to the standard metadata of the page
+        let metadata = PageMetadata::new(&doc);
         // Title
         if !manga_builder.has_title() {
-            if let Some(title) = self.select_string(&config.manga.title, doc.clone())? {
+            let title = self
+                .select_string(&url, &config.manga.title, doc.clone())?
+                .map(|title| (title, MetadataSource::Config))
+                .or_else(|| metadata.title());
+            if let Some((title, source)) = title {
                 manga_builder.title(title);
+                sources.title = Some(source);
             }
         }
         // Description
         if !manga_builder.has_description() {
-            if let Some(description) = self.select_string(&config.manga.description, doc.clone())? {
+            let description = self
+                .select_string(&url, &config.manga.description, doc.clone())?
+                .map(|description| (description, MetadataSource::Config))
+                .or_else(|| metadata.description());
+            if let Some((description, source)) = description {
                 manga_builder.description(description);
+                sources.description = Some(source);
             }
         }
         // Cover URL
-        if let Some(cover_url) = config
+        let cover_url = match config
             .manga
             .cover_url
             .as_ref()
             .map_or(Ok(None), |selector| self.select_url(&url, selector, doc.clone()))?
         {
-            manga_builder.cover_url(cover_url);
+            Some(cover_url) => Some((cover_url, MetadataSource::Config)),
+            None => metadata
+                .cover_url()
+                .and_then(|(cover_url, source)| Some((url.join(&cover_url).ok()?, source))),
+        };
+        if let Some((cover_url, source)) = cover_url {
+            if let Some(cover_url) = self.image_urls(vec![cover_url], config, &url).await.pop() {
+                manga_builder.cover_url(cover_url);
+                sources.cover_url = Some(source);
+            }
         }
         // Authors
-        manga_builder.authors(
-            config
-                .manga
-                .authors
-                .as_ref()
-                .map_or(Ok(vec![]), |selector| self.select_string_array(selector, doc.clone()))?,
+        let (authors, source) = with_fallback(
+            config.manga.authors.as_ref().map_or(Ok(vec![]), |selector| {
+                self.select_string_array(&url, selector, doc.clone())
+            })?,
+            || metadata.authors(),
         );
+        manga_builder.authors(authors);
+        sources.authors = source;
         // Genres
-        manga_builder.genres(
-            config
+        let (genres, source) = with_fallback(
+            config.manga.genres.as_ref().map_or(Ok(vec![]), |selector| {
+                self.select_string_array(&url, selector, doc.clone())
+            })?,
+            || metadata.genres(),
+        );
+        manga_builder.genres(genres);
+        sources.genres = source;
+        manga_builder.sources(sources.clone());
+        // Artists
+        manga_builder.artists(config.manga.artists.as_ref().map_or(Ok(vec![]), |selector| {
+            self.select_string_array(&url, selector, doc.clone())
+        })?);
+        // Content rating
+        if !manga_builder.has_content_rating() {
+            if let Some(content_rating) = config
                 .manga
-                .genres
+                .content_rating
                 .as_ref()
-                .map_or(Ok(vec![]), |selector| self.select_string_array(selector, doc.clone()))?,
-        );
-        // Alternative Titles
-        manga_builder.alternative_titles(
-            config
+                .map_or(Ok(None), |selector| self.select_string(&url, selector, doc.clone()))?
+            {
+                manga_builder.content_rating(ContentRating::from_text(&content_rating));
+            }
+        }
+        // Publication type
+        if !manga_builder.has_publication_type() {
+            if let Some(publication_type) = config
                 .manga
-                .alt_titles
+                .publication_type
                 .as_ref()
-                .map_or(Ok(vec![]), |selector| self.select_string_array(selector, doc.clone()))?,
-        );
+                .map_or(Ok(None), |selector| self.select_string(&url, selector, doc.clone()))?
+            {
+                manga_builder.publication_type(PublicationType::from_text(&publication_type));
+            }
+        }
+        // Year
+        if !manga_builder.has_year() {
+            if let Some(year) = config
+                .manga
+                .year
+                .as_ref()
+                .map_or(Ok(None), |selector| self.select_string(&url, selector, doc.clone()))?
+                .and_then(|year| parse_year(&year))
+            {
+                manga_builder.year(year);
+            }
+        }
+        // Original language
+        if !manga_builder.has_original_language() {
+            if let Some(original_language) = config
+                .manga
+                .original_language
+                .as_ref()
+                .map_or(Ok(None), |selector| self.select_string(&url, selector, doc.clone()))?
+            {
+                manga_builder.original_language(original_language);
+            }
+        }
+        // Alternative Titles
+        manga_builder.alternative_titles(config.manga.alt_titles.as_ref().map_or(Ok(vec![]), |selector| {
+            self.select_string_array(&url, selector, doc.clone())
+        })?);
         // Chapters
         if !manga_builder.has_chapters() {
             manga_builder.chapters(self.chapters(&url, config, doc).await?);
         }
+        manga_builder.source_id(config.name.clone());
 
         Ok(manga_builder)
     }
 
-    fn manga_status(&self, status: Option<String>) -> bool {
-        if let Some(status) = status {
-            matches!(
-                status.to_lowercase().as_str(),
-                "ongoing" | "on-going" | "updating" | "live"
-            )
-        } else {
-            true
-        }
-    }
-
-    fn get_search_configs_for_hostname(&self, hostname: &str) -> Vec<&MangaScraperConfig> {
+    fn get_search_configs_for_hostname(&self, hostname: &str) -> Vec<Arc<MangaScraperConfig>> {
         let mut accepted_configs = vec![];
-        for config in self.configs.iter() {
+        for config in self.configs() {
             for search in config.search.iter() {
                 if search.hostnames.contains(&hostname.to_string()) {
-                    accepted_configs.push(config);
+                    accepted_configs.push(config.clone());
                 }
             }
         }
         accepted_configs
     }
 
-    fn get_configs_for_url(&self, url: &Url, doc: DocWrapper) -> Vec<&MangaScraperConfig> {
-        let hostname = url.host_str().unwrap().to_string();
-        let mut accepted_configs = vec![];
-        for config in self.configs.iter() {
-            if config.accept.hostnames.contains(&hostname) {
-                accepted_configs.push(config);
+    /// HTTP settings of the first config for the hostname, used to fetch a page before its configs are known
+    fn http_config_for_url(&self, url: &Url) -> HttpConfig {
+        let hostname = url.host_str().unwrap_or_default();
+        self.configs()
+            .iter()
+            .find(|config| config.accept.hostnames.iter().any(|accepted| accepted == hostname))
+            .map(|config| config.http.clone())
+            .unwrap_or_default()
+    }
+
+    /// The theme of the page at `url` and the configs that would be tried on it, in order
+    pub async fn detect(&self, url: &Url) -> Result<Detection, ScrapeError> {
+        let http = self.http_config_for_url(url);
+        let (doc, url) = fetch_doc(url, &http, None).await?;
+        let themes = fingerprint(&doc);
+        let candidates = self
+            .config_candidates(&url, &doc, &themes)
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .collect();
+        Ok(Detection {
+            url,
+            themes,
+            candidates,
+        })
+    }
+
+    /// Configs accepting the page, with why they do
+    ///
+    /// Configs are in priority order, and within a priority those for the hostname go before those matching the
+    /// page, which go before those for the theme of the page
+    fn config_candidates(
+        &self,
+        url: &Url,
+        doc: &DocWrapper,
+        themes: &[ThemeMatch],
+    ) -> Vec<(Arc<MangaScraperConfig>, ConfigCandidate)> {
+        let hostname = url.host_str().unwrap_or_default().to_string();
+        let mut accepted = vec![];
+        for config in self.configs() {
+            let reason = if config.accept.hostnames.contains(&hostname) {
+                Some((AcceptReason::Hostname, 1.0))
+            } else if config.accept.selectors.iter().any(|selector| doc.matches(selector)) {
+                Some((AcceptReason::Selector, 1.0))
             } else {
-                for selector in config.accept.selectors.iter() {
-                    if doc.select_first(selector).is_ok() {
-                        accepted_configs.push(config);
-                        break;
-                    }
-                }
+                themes
+                    .iter()
+                    .filter(|found| found.confidence >= MIN_CONFIDENCE)
+                    .find(|found| config.accept.themes.contains(&found.theme))
+                    .map(|found| (AcceptReason::Theme(found.theme), found.confidence))
+            };
+            if let Some((reason, confidence)) = reason {
+                let candidate = ConfigCandidate {
+                    config: config.name.clone(),
+                    reason,
+                    confidence,
+                };
+                accepted.push((config, candidate));
             }
         }
+        // Sorting is stable, so configs of the same priority and reason keep their order
+        accepted.sort_by(|(a, a_candidate), (b, b_candidate)| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| reason_rank(&a_candidate.reason).cmp(&reason_rank(&b_candidate.reason)))
+                .then_with(|| {
+                    b_candidate
+                        .confidence
+                        .partial_cmp(&a_candidate.confidence)
+                        .unwrap_or(Ordering::Equal)
+                })
+        });
+        accepted
+    }
+
+    fn get_configs_for_url(&self, url: &Url, doc: DocWrapper) -> Vec<Arc<MangaScraperConfig>> {
+        let themes = fingerprint(&doc);
+        if let Some(theme) = themes.first() {
+            debug!(
+                "[config] {} looks like {} ({:.2})",
+                url.as_str(),
+                theme.theme,
+                theme.confidence
+            );
+        }
+        let accepted_configs: Vec<_> = self
+            .config_candidates(url, &doc, &themes)
+            .into_iter()
+            .map(|(config, _)| config)
+            .collect();
         if log_enabled!(log::Level::Debug) {
             debug!(
                 "[config] found {} config(s) for {}",
@@ -524,20 +995,35 @@ impl GenericScraper {
     }
 }
 
+/// Configs for the hostname go first, then those matching the page and then those for its theme
+fn reason_rank(reason: &AcceptReason) -> u8 {
+    match reason {
+        AcceptReason::Hostname => 0,
+        AcceptReason::Selector => 1,
+        AcceptReason::Theme(_) => 2,
+    }
+}
+
 #[async_trait::async_trait]
 impl MangaScraper for GenericScraper {
     async fn manga(&self, url: &Url) -> Result<Manga, ScrapeError> {
-        let (doc, url) = fetch_doc(url).await?;
+        let http = self.http_config_for_url(url);
+        let fetched = fetch_doc(url, &http, None).await?;
 
-        let accepted_configs = self.get_configs_for_url(&url, doc.clone());
+        let accepted_configs = self.get_configs_for_url(&fetched.1, fetched.0.clone());
 
         let mut errors = HashMap::<String, ScrapeError>::new();
         let mut manga_builder = MangaBuilder::new();
+        let mut sources = MangaSources::default();
         for config in accepted_configs {
-            match self
-                .full_manga(url.clone(), config, doc.clone(), &mut manga_builder)
-                .await
-            {
+            let manga = match fetch_doc_for_config(url, &config, &http, &fetched).await {
+                Ok((doc, page_url)) => {
+                    self.full_manga(page_url, &config, doc, &mut manga_builder, &mut sources)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            match manga {
                 Ok(manga_builder) => match manga_builder.build() {
                     Ok(manga) => return Ok(manga),
                     Err(e) => {
@@ -551,7 +1037,7 @@ impl MangaScraper for GenericScraper {
         }
 
         if errors.is_empty() {
-            let hostname = url.host_str().unwrap().to_string();
+            let hostname = fetched.1.host_str().unwrap().to_string();
             Err(ScrapeError::WebsiteNotSupported(format!(
                 "No scrapers found for {hostname}"
             )))
@@ -561,12 +1047,17 @@ impl MangaScraper for GenericScraper {
     }
 
     async fn chapter_images(&self, chapter_url: &Url) -> Result<Vec<Url>, ScrapeError> {
-        let (doc, url) = fetch_doc(chapter_url).await?;
-        let accepted_configs = self.get_configs_for_url(&url, doc.clone());
+        let http = self.http_config_for_url(chapter_url);
+        let fetched = fetch_doc(chapter_url, &http, None).await?;
+        let accepted_configs = self.get_configs_for_url(&fetched.1, fetched.0.clone());
 
         let mut errors = HashMap::<String, ScrapeError>::new();
         for config in accepted_configs {
-            match self.images(url.clone(), config, doc.clone()).await {
+            let images = match fetch_doc_for_config(chapter_url, &config, &http, &fetched).await {
+                Ok((doc, url)) => self.images(url, &config, doc).await,
+                Err(e) => Err(e),
+            };
+            match images {
                 Ok(images) => return Ok(images),
                 Err(e) => {
                     errors.insert(config.name.clone(), e);
@@ -575,7 +1066,7 @@ impl MangaScraper for GenericScraper {
         }
 
         if errors.is_empty() {
-            let hostname = url.host_str().unwrap().to_string();
+            let hostname = fetched.1.host_str().unwrap().to_string();
             Err(ScrapeError::WebsiteNotSupported(format!(
                 "No scrapers found for {hostname}"
             )))
@@ -598,19 +1089,22 @@ impl MangaScraper for GenericScraper {
         */
     }
 
-    async fn search(&self, query: &str, hostnames: &[String]) -> Result<Vec<SearchManga>, ScrapeError> {
+    async fn search_page(&self, query: &str, hostnames: &[String], page: u32) -> Result<SearchPage, ScrapeError> {
         let mut err = None;
-        let mut results = vec![];
+        let mut results = SearchPage::empty(page);
         for hostname in hostnames {
             let accepted_configs = self.get_search_configs_for_hostname(hostname);
             for config in accepted_configs {
-                match self.do_search(&config, hostname, query).await {
-                    Ok(mut search_manga) => results.append(&mut search_manga),
+                match self.do_search(&config, hostname, query, page).await {
+                    Ok(mut search_page) => {
+                        results.results.append(&mut search_page.results);
+                        results.has_more |= search_page.has_more;
+                    }
                     Err(e) => err = Some(e),
                 };
             }
         }
-        if !results.is_empty() {
+        if !results.results.is_empty() {
             return Ok(results);
         }
 
@@ -619,7 +1113,7 @@ impl MangaScraper for GenericScraper {
 
     fn searchable_hostnames(&self) -> Vec<String> {
         let mut hostnames = vec![];
-        for config in self.configs.iter() {
+        for config in self.configs() {
             for search in config.search.iter() {
                 hostnames.append(&mut search.hostnames.clone());
             }
@@ -633,39 +1127,159 @@ impl MangaScraper for GenericScraper {
     }
 }
 
-#[derive(Clone)]
-struct DocWrapper(pub NodeRef);
-unsafe impl Send for DocWrapper {}
-unsafe impl Sync for DocWrapper {}
-impl Deref for DocWrapper {
-    type Target = NodeRef;
+fn http_method(method: &str) -> Method {
+    match method {
+        "post" => Method::POST,
+        _ => Method::GET,
+    }
+}
+
+/// Fetch the search results at `url` with the method, headers and body of the search config
+async fn fetch_search(
+    search_config: &SearchConfig,
+    http: &HttpConfig,
+    url: &Url,
+    variables: &[(&str, &str)],
+) -> Result<(DocWrapper, Url), ScrapeError> {
+    fetch_doc_config(
+        url,
+        http,
+        None,
+        http_method(&search_config.method),
+        &request::render_headers(&search_config.headers, variables),
+        search_config.body.as_ref().map(|body| body.render(variables)),
+    )
+    .await
+}
+
+/// The first year (1900 to 2099) in the text
+fn parse_year(text: &str) -> Option<u32> {
+    YEAR.find(text).and_then(|year| year.as_str().parse().ok())
+}
+
+/// The values the config selected, or else those of the fallback
+fn with_fallback(
+    selected: Vec<String>,
+    fallback: impl FnOnce() -> Option<(Vec<String>, MetadataSource)>,
+) -> (Vec<String>, Option<MetadataSource>) {
+    if !selected.is_empty() {
+        return (selected, Some(MetadataSource::Config));
+    }
+    match fallback() {
+        Some((values, source)) => (values, Some(source)),
+        None => (vec![], None),
+    }
+}
+
+/// A request with the cookies, rate limit, timeout, referer and headers of the config, and the client to send it with
+fn config_request(
+    url: &Url,
+    http: &HttpConfig,
+    page: Option<&Url>,
+    method: Method,
+    headers: &HashMap<String, String>,
+    body: Option<RequestBody>,
+) -> Result<(&'static ClientWithMiddleware, reqwest::Request), ScrapeError> {
+    let client = if http.follow_redirects {
+        &*HTTP_CLIENT
+    } else {
+        &*HTTP_CLIENT_NO_REDIRECT
+    };
+    for cookie in http.cookie_pairs() {
+        COOKIE_JAR.add_cookie_str(&cookie, url);
+    }
+    if let Some(host) = url.host_str() {
+        RATE_LIMITER.configure(host, &http.rate_limit);
+    }
+
+    let mut request = client.request(method, url.clone()).timeout(http.timeout());
+    if let Some(referer) = http.referer(url, page) {
+        request = request
+            .header(REFERER, referer.as_str())
+            .header(ORIGIN, referer.origin().ascii_serialization());
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    request = match body {
+        Some(RequestBody::Form(fields)) => request.form(&fields),
+        Some(RequestBody::Json(value)) => request
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(value.to_string()),
+        Some(RequestBody::Raw(body)) => request.body(body),
+        None => request,
+    };
+
+    // Configured headers replace the defaults, including the content type of the body,
+    // and the headers of the request replace those of the config
+    let mut header_map = HeaderMap::new();
+    for (name, value) in http.headers.iter().chain(headers) {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| ScrapeError::WebScrapingError(format!("Invalid header name {name}: {e}")))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| ScrapeError::WebScrapingError(format!("Invalid header value {value}: {e}")))?;
+        header_map.insert(name, value);
     }
+    request = request.headers(header_map);
+
+    Ok((client, request.build()?))
 }
 
-fn html_to_doc(html: &str) -> Result<DocWrapper, ScrapeError> {
-    let doc = std::panic::catch_unwind(|| kuchiki::parse_html().one(html))
-        .map_err(|_e| ScrapeError::WebScrapingError("Could not parse HTML".to_string()))?;
-    Ok(DocWrapper(doc))
+/// Whether an image can be loaded, checked with a `HEAD` request
+///
+/// Servers that do not allow `HEAD` are asked for the first byte of the image instead
+async fn image_available(url: &Url, http: &HttpConfig, page: &Url) -> bool {
+    if let Some(available) = fixtures::available(url) {
+        return available;
+    }
+    match image_status(url, http, page, Method::HEAD, &HashMap::new()).await {
+        Some(StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED) => {
+            let range = HashMap::from([(String::from("Range"), String::from("bytes=0-0"))]);
+            image_status(url, http, page, Method::GET, &range)
+                .await
+                .is_some_and(|status| status.is_success())
+        }
+        status => status.is_some_and(|status| status.is_success()),
+    }
 }
 
-async fn fetch_doc_config<T>(url: &Url, method: Method, body: Option<T>) -> Result<(DocWrapper, Url), ScrapeError>
-where
-    T: Into<Body>,
-{
-    let mut request = HTTP_CLIENT
-        .request(method, url.clone())
-        .header("Referer", url.to_string())
-        .header("Origin", url.to_string())
-        .timeout(Duration::from_secs(5));
+/// Status of a request for an image, `None` when it could not be made
+async fn image_status(
+    url: &Url,
+    http: &HttpConfig,
+    page: &Url,
+    method: Method,
+    headers: &HashMap<String, String>,
+) -> Option<StatusCode> {
+    let response = match config_request(url, http, Some(page), method, headers, None) {
+        Ok((client, request)) => client.execute(request).await,
+        Err(e) => {
+            warn!("[images] could not request {url}: {e}");
+            return None;
+        }
+    };
+    match response {
+        Ok(response) => Some(response.status()),
+        Err(e) => {
+            debug!("[images] {url} is not available: {e}");
+            None
+        }
+    }
+}
 
-    if let Some(body) = body {
-        request = request.body(body);
+/// Fetch a document with the HTTP settings of a config, `page` is the page the request is made from
+async fn fetch_doc_config(
+    url: &Url,
+    http: &HttpConfig,
+    page: Option<&Url>,
+    method: Method,
+    headers: &HashMap<String, String>,
+    body: Option<RequestBody>,
+) -> Result<(DocWrapper, Url), ScrapeError> {
+    if let Some(fixture) = fixtures::fixture(url) {
+        return fixture;
     }
 
-    let response = HTTP_CLIENT.execute(request.build()?).await?;
+    let (client, request) = config_request(url, http, page, method, headers, body)?;
+    let response = client.execute(request).await?;
 
     let response = match response.error_for_status() {
         Ok(response) => response,
@@ -679,10 +1293,29 @@ where
     };
 
     let url = response.url().clone();
-    let html = response.text().await?;
-    Ok((html_to_doc(&html)?, url))
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(String::from);
+    let body = response.text().await?;
+    Ok((DocWrapper::from_body(&body, content_type.as_deref())?, url))
 }
 
-async fn fetch_doc(url: &Url) -> Result<(DocWrapper, Url), ScrapeError> {
-    fetch_doc_config(url, Method::GET, None::<String>).await
+async fn fetch_doc(url: &Url, http: &HttpConfig, page: Option<&Url>) -> Result<(DocWrapper, Url), ScrapeError> {
+    fetch_doc_config(url, http, page, Method::GET, &HashMap::new(), None).await
+}
+
+/// The page at `url` as the config fetches it, `fetched` is reused when it was fetched with the same settings
+async fn fetch_doc_for_config(
+    url: &Url,
+    config: &MangaScraperConfig,
+    http: &HttpConfig,
+    fetched: &(DocWrapper, Url),
+) -> Result<(DocWrapper, Url), ScrapeError> {
+    if config.http == *http {
+        return Ok(fetched.clone());
+    }
+    debug!("[config] fetching {} again with the HTTP settings of {}", url.as_str(), config.name);
+    fetch_doc(url, &config.http, None).await
 }
@@ -0,0 +1,610 @@
+use std::{path::Path, slice};
+
+use chrono::NaiveDate;
+use itertools::Itertools;
+use kuchiki::NodeRef;
+use regex::Regex;
+use reqwest::Url;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    config::{
+        schema,
+        status_map::StatusMap,
+        validate::{self, ConfigIssue},
+    },
+    error::ScrapeError,
+    model::MangaStatus,
+    util::{date::try_parse_date, kuchiki_elements::ElementsTrait},
+};
+
+use super::{
+    document::DocWrapper,
+    fingerprint::{fingerprint, MIN_CONFIDENCE},
+};
+
+lazy_static::lazy_static! {
+    static ref CHAPTER_TITLE: Regex = Regex::new(r"(?i)\b(ch(apter|\.)?|episode|ep\.?)\s*\d").unwrap();
+    static ref IDENTIFIER: Regex = Regex::new(r"^-?[A-Za-z_][\w-]*$").unwrap();
+    static ref HAS_DIGITS: Regex = Regex::new(r"\d").unwrap();
+}
+
+/// Elements whose text is never a field of the manga
+const SKIPPED_TAGS: [&str; 6] = ["head", "script", "style", "noscript", "title", "meta"];
+/// Words in the class or id of the element with the description
+const DESCRIPTION_HINTS: [&str; 5] = ["summary", "description", "synopsis", "desc", "story"];
+/// Words in the class, id or alt of the cover, or in the class of one of its parents
+const COVER_HINTS: [&str; 4] = ["cover", "thumb", "poster", "summary_image"];
+/// Labels in front of the alternative titles
+const ALT_TITLE_LABELS: [&str; 4] = ["alternative", "associated", "other name", "also known"];
+/// Labels in front of the publication type
+const TYPE_LABELS: [&str; 2] = ["type", "format"];
+/// Labels in front of the year
+const YEAR_LABELS: [&str; 3] = ["release", "year", "published"];
+/// Attributes lazily loaded images keep their URL in
+const LAZY_ATTRIBUTES: [&str; 4] = ["data-src", "data-lazy-src", "data-original", "data-cfsrc"];
+/// Formats tried on the date of the sample chapter
+const DATE_FORMATS: [&str; 8] = [
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+    "%Y-%m-%d",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d.%m.%Y",
+];
+
+/// A sample manga page and what is known to be on it
+pub struct SamplePage<'a> {
+    /// URL of the manga page, its hostname is accepted by the draft
+    pub url: &'a Url,
+    pub html: &'a str,
+    pub title: &'a str,
+    /// Title of one of the chapters, without it the first link that looks like a chapter is used
+    pub chapter_title: Option<&'a str>,
+    /// HTML of a chapter, the images are looked for on the manga page without it
+    pub chapter_html: Option<&'a str>,
+    /// URL of one of the images of the chapter
+    pub image_url: Option<&'a str>,
+}
+
+/// A config inferred from a sample page, to be checked and finished by hand
+pub struct ConfigDraft {
+    pub config: Value,
+    /// Fields that could not be inferred, or are guesses
+    pub notes: Vec<String>,
+}
+
+impl ConfigDraft {
+    /// Name of the draft config file
+    pub fn file_name(&self) -> String {
+        format!("{}.yaml", self.config["name"].as_str().unwrap_or("draft"))
+    }
+
+    pub fn to_yaml(&self) -> String {
+        String::from("# yaml-language-server: $schema=../schema/config.schema.yaml\n") + &schema::to_yaml(&self.config)
+    }
+
+    /// Problems of the draft according to the config schema
+    pub fn issues(&self) -> Vec<ConfigIssue> {
+        validate::validate_config(Path::new(&self.file_name()), &self.config)
+    }
+}
+
+/// Draft a config for the website of the sample page
+///
+/// Elements containing the known values are looked up and selectors are built from their tags, ids and classes
+/// (leaving out those with digits, which tend to differ between pages), adding the classes of their parents until
+/// the selector finds the element. The other fields are found by the usual class names, labels and links
+pub fn draft_config(page: &SamplePage) -> Result<ConfigDraft, ScrapeError> {
+    let DocWrapper::Html(doc) = DocWrapper::from_html(page.html)? else {
+        unreachable!("HTML is parsed as HTML");
+    };
+    let hostname = page.url.host_str().unwrap_or_default();
+    let mut notes = vec![];
+
+    let mut accept = Map::new();
+    accept.insert(String::from("hostnames"), json!([hostname]));
+    if let Some(theme) = fingerprint(&DocWrapper::Html(doc.clone()))
+        .into_iter()
+        .find(|theme| theme.confidence >= MIN_CONFIDENCE)
+    {
+        notes.push(format!(
+            "the page looks like the {} theme, which may have a config already",
+            theme.theme
+        ));
+        accept.insert(String::from("themes"), json!([theme.theme.name()]));
+    }
+
+    let mut manga = Map::new();
+    let title = find_text(&doc, page.title)
+        .and_then(|title| build_selector(&doc, &[title]))
+        .ok_or_else(|| ScrapeError::WebScrapingError(format!("The title {:?} is not on the page", page.title)))?;
+    manga.insert(String::from("title"), json!(title));
+    manga.insert(String::from("description"), description(&doc, &mut notes));
+    let fields = [
+        ("cover_url", cover(&doc, page.title)),
+        ("status", status(&doc)),
+        ("authors", links(&doc, &["a[href*='author']"])),
+        ("artists", links(&doc, &["a[href*='artist']"])),
+        ("genres", links(&doc, &["a[href*='genre']", "a[href*='/tag']"])),
+        ("alt_titles", labelled(&doc, &ALT_TITLE_LABELS)),
+        ("publication_type", labelled(&doc, &TYPE_LABELS)),
+        ("year", labelled(&doc, &YEAR_LABELS)),
+    ];
+    for (field, value) in fields {
+        match value {
+            Some(value) => {
+                manga.insert(String::from(field), value);
+            }
+            None => notes.push(format!("no {field} found")),
+        }
+    }
+    let (chapter, date_format) = chapter(&doc, page.chapter_title, &mut notes)?;
+    manga.insert(String::from("chapter"), chapter);
+
+    let chapter_doc = match page.chapter_html {
+        Some(html) => match DocWrapper::from_html(html)? {
+            DocWrapper::Html(chapter_doc) => chapter_doc,
+            DocWrapper::Json(_) => unreachable!("HTML is parsed as HTML"),
+        },
+        None => doc.clone(),
+    };
+    let image_selector = match page.image_url {
+        Some(image_url) => images(&chapter_doc, page.url, image_url)
+            .ok_or_else(|| ScrapeError::WebScrapingError(format!("The image {image_url} is not on the page")))?,
+        None => {
+            notes.push(String::from(
+                "image_selector is a guess, give the URL of an image to find it",
+            ));
+            json!("img")
+        }
+    };
+
+    let config = json!({
+        "version": crate::config::migrate::CONFIG_VERSION,
+        "name": config_name(hostname),
+        "accept": accept,
+        "manga": manga,
+        "images": { "image_selector": image_selector },
+        "date_formats": date_format.into_iter().collect::<Vec<_>>(),
+    });
+    Ok(ConfigDraft { config, notes })
+}
+
+/// First label of the hostname, without www
+fn config_name(hostname: &str) -> String {
+    let hostname = hostname.strip_prefix("www.").unwrap_or(hostname);
+    hostname.split('.').next().unwrap_or(hostname).to_string()
+}
+
+/// A selector, with the attributes to take the text from when it is not the text of the element
+fn selector_value(selector: String, attributes: &[&str]) -> Value {
+    if attributes.is_empty() {
+        return json!(selector);
+    }
+    json!({
+        "selector": selector,
+        "options": {
+            "text_selection": {
+                "type": "attributes",
+                "attributes": attributes.iter().unique().collect::<Vec<_>>(),
+            },
+        },
+    })
+}
+
+fn description(doc: &NodeRef, notes: &mut Vec<String>) -> Value {
+    let described = elements(&body(doc))
+        .filter(|node| {
+            let names = [attr(node, "class"), attr(node, "id")]
+                .into_iter()
+                .flatten()
+                .join(" ")
+                .to_lowercase();
+            DESCRIPTION_HINTS.iter().any(|hint| names.contains(hint))
+        })
+        .filter(|node| normalize(&node.text_contents()).len() >= 40)
+        // The innermost of the elements with the most text
+        .max_by_key(|node| (normalize(&node.text_contents()).len(), node.ancestors().count()));
+    if let Some(selector) = described.and_then(|node| build_selector(doc, &[node])) {
+        return json!(selector);
+    }
+
+    notes.push(String::from("the description is taken from the meta tags"));
+    let meta = ["meta[property='og:description']", "meta[name='description']"]
+        .into_iter()
+        .find(|selector| !select(doc, selector).is_empty())
+        .unwrap_or("meta[property='og:description']");
+    selector_value(meta.to_string(), &["content"])
+}
+
+fn cover(doc: &NodeRef, title: &str) -> Option<Value> {
+    let title = normalize(title);
+    let image = select(&body(doc), "img").into_iter().find(|image| {
+        let alt = normalize(&attr(image, "alt").unwrap_or_default());
+        let names = image
+            .inclusive_ancestors()
+            .take(3)
+            .flat_map(|node| [attr(&node, "class"), attr(&node, "id")])
+            .flatten()
+            .join(" ")
+            .to_lowercase();
+        (!alt.is_empty() && alt.contains(&title)) || COVER_HINTS.iter().any(|hint| names.contains(hint))
+    });
+    if let Some(selector) = image.and_then(|image| build_selector(doc, &[image])) {
+        return Some(selector_value(selector, &["data-src", "src"]));
+    }
+    let meta = "meta[property='og:image']";
+    (!select(doc, meta).is_empty()).then(|| selector_value(meta.to_string(), &["content"]))
+}
+
+fn status(doc: &NodeRef) -> Option<Value> {
+    let status_map = StatusMap::default();
+    elements(&body(doc))
+        .find(|node| {
+            let text = node.own_text();
+            !text.is_empty() && status_map.status(&text) != MangaStatus::Unknown
+        })
+        .and_then(|node| build_selector(doc, &[node]))
+        .map(|selector| json!(selector))
+}
+
+/// Links to the pages of authors, genres and the like, by the first of the selectors that finds any
+fn links(doc: &NodeRef, selectors: &[&str]) -> Option<Value> {
+    selectors
+        .iter()
+        .map(|selector| select(&body(doc), selector))
+        .find(|links| !links.is_empty())
+        .and_then(|links| build_selector(doc, &links))
+        .map(|selector| json!(selector))
+}
+
+/// The element after a label starting with one of the words, like "Alternative Titles:"
+fn labelled(doc: &NodeRef, labels: &[&str]) -> Option<Value> {
+    let label = elements(&body(doc)).find(|node| {
+        let text = node.own_text().to_lowercase();
+        text.len() < 40 && labels.iter().any(|label| text.starts_with(label))
+    })?;
+    let value = label
+        .inclusive_ancestors()
+        .take(3)
+        .find_map(|node| next_element(&node))?;
+    build_selector(doc, &[value]).map(|selector| json!(selector))
+}
+
+/// The chapter selectors and the format of the date of the sample chapter
+fn chapter(
+    doc: &NodeRef,
+    chapter_title: Option<&str>,
+    notes: &mut Vec<String>,
+) -> Result<(Value, Option<&'static str>), ScrapeError> {
+    let (target, (item, items)) = match chapter_title {
+        Some(chapter_title) => {
+            let target = find_text(doc, chapter_title).ok_or_else(|| {
+                ScrapeError::WebScrapingError(format!("The chapter {chapter_title:?} is not on the page"))
+            })?;
+            let repeated = repeated_item(&target).ok_or_else(|| {
+                ScrapeError::WebScrapingError(format!("The chapter {chapter_title:?} is not in a list"))
+            })?;
+            (target, repeated)
+        }
+        None => select(&body(doc), "a")
+            .into_iter()
+            .filter(|link| CHAPTER_TITLE.is_match(&link.text_contents()))
+            .find_map(|link| Some((link.clone(), repeated_item(&link)?)))
+            .ok_or_else(|| ScrapeError::WebScrapingError(String::from("No list of chapters found")))?,
+    };
+
+    let not_found = || ScrapeError::WebScrapingError(String::from("No selector finds the chapters"));
+    let base = build_selector(doc, &items).ok_or_else(not_found)?;
+    let title = build_selector(&item, slice::from_ref(&target)).ok_or_else(not_found)?;
+    let link = target
+        .inclusive_ancestors()
+        .take_while(|node| *node != item)
+        .chain([item.clone()])
+        .find(|node| tag(node) == "a")
+        .or_else(|| select(&item, "a").into_iter().next())
+        .ok_or_else(|| ScrapeError::WebScrapingError(String::from("The chapters have no links")))?;
+    let url = build_selector(&item, &[link]).ok_or_else(not_found)?;
+
+    let mut chapter = json!({
+        "base": base,
+        "title": title,
+        "url": selector_value(url, &["href"]),
+    });
+    let date = elements(&item)
+        .filter(|node| !node.inclusive_ancestors().any(|ancestor| ancestor == target))
+        .filter(|node| !target.ancestors().any(|ancestor| ancestor == *node))
+        .find(|node| is_date(&node.own_text()));
+    let mut date_format = None;
+    match date.and_then(|date| Some((build_selector(&item, slice::from_ref(&date))?, date))) {
+        Some((selector, date)) => {
+            chapter["date"] = json!(selector);
+            date_format = guess_date_format(&date.own_text());
+            if date_format.is_none() {
+                notes.push(format!("the format of the date {:?} is not known", date.own_text()));
+            }
+        }
+        None => notes.push(String::from("no chapter date found")),
+    }
+    Ok((chapter, date_format))
+}
+
+/// A selector for all images like the one at `image_url`, with the attributes to take the URL from
+fn images(doc: &NodeRef, page_url: &Url, image_url: &str) -> Option<Value> {
+    let wanted = page_url.join(image_url).ok();
+    let (image, attribute) = select(doc, "img").into_iter().find_map(|image| {
+        let element = image.as_element()?;
+        let attributes = element.attributes.borrow();
+        let attribute = attributes.map.iter().find_map(|(name, attribute)| {
+            let value = attribute.value.trim();
+            let found = value == image_url || (wanted.is_some() && page_url.join(value).ok() == wanted);
+            found.then(|| name.local.to_string())
+        })?;
+        drop(attributes);
+        Some((image.clone(), attribute))
+    })?;
+    // The images of the chapter are the ones in the nearest parent with more than one
+    let container = image
+        .ancestors()
+        .find(|ancestor| select(ancestor, "img").len() > 1)
+        .unwrap_or_else(|| body(doc));
+    let step = step(&image, false);
+    let images: Vec<_> = select(&container, &step);
+    let selector = build_selector(doc, &images)?;
+    // The other images may not be loaded the way the sample is
+    let lazy = LAZY_ATTRIBUTES
+        .into_iter()
+        .filter(|lazy| images.iter().any(|image| attr(image, lazy).is_some()));
+    let attributes: Vec<_> = lazy.chain([attribute.as_str(), "src"]).collect();
+    Some(selector_value(selector, &attributes))
+}
+
+/// The element that repeats in its parent (the chapter) with the target in it, and all of its repetitions
+fn repeated_item(target: &NodeRef) -> Option<(NodeRef, Vec<NodeRef>)> {
+    let mut node = target.clone();
+    loop {
+        let parent = node
+            .parent()
+            .filter(|parent| !matches!(tag(parent).as_str(), "" | "body" | "html"))?;
+        let repetitions: Vec<_> = parent
+            .children()
+            .filter(|sibling| tag(sibling) == tag(&node) && classes(sibling) == classes(&node))
+            .collect();
+        if repetitions.len() > 1 {
+            return Some((node, repetitions));
+        }
+        node = parent;
+    }
+}
+
+/// Selector from `scope` for the targets, which share the step of the first target
+///
+/// Steps of parents with an id or classes are added in front until the selector finds exactly the targets. When no
+/// selector does, one that finds the first target first is accepted
+fn build_selector(scope: &NodeRef, targets: &[NodeRef]) -> Option<String> {
+    let first = targets.first()?;
+    let mut steps = vec![if targets.len() == 1 {
+        step(first, true)
+    } else {
+        common_step(targets)
+    }];
+    let mut ancestors = first
+        .ancestors()
+        .take_while(|ancestor| ancestor != scope)
+        .filter(is_informative)
+        .filter(|ancestor| {
+            targets
+                .iter()
+                .all(|target| target.ancestors().any(|node| node == *ancestor))
+        })
+        .take(4);
+    // A bare tag says little about the element, so the nearest parent that says more goes with it
+    if !steps[0].contains(['.', '#']) {
+        if let Some(ancestor) = ancestors.next() {
+            steps.push(step(&ancestor, true));
+        }
+    }
+    loop {
+        let selector = steps.iter().rev().join(" ");
+        let found = select(scope, &selector);
+        let all_found = targets.iter().all(|target| found.contains(target));
+        if all_found && found.len() == targets.len() {
+            return Some(selector);
+        }
+        match ancestors.next() {
+            Some(ancestor) => steps.push(step(&ancestor, true)),
+            None => return (all_found && found.first() == Some(first)).then_some(selector),
+        }
+    }
+}
+
+/// Tag with the id or the classes of the element
+fn step(node: &NodeRef, with_id: bool) -> String {
+    match id(node).filter(|_| with_id) {
+        Some(id) => format!("{}#{id}", tag(node)),
+        None => std::iter::once(tag(node)).chain(classes(node)).join("."),
+    }
+}
+
+/// Tag with the classes all of the elements have
+fn common_step(nodes: &[NodeRef]) -> String {
+    let first = &nodes[0];
+    let classes = classes(first)
+        .into_iter()
+        .filter(|class| nodes.iter().all(|node| classes(node).contains(class)));
+    std::iter::once(tag(first)).chain(classes).join(".")
+}
+
+fn is_informative(node: &NodeRef) -> bool {
+    id(node).is_some() || !classes(node).is_empty()
+}
+
+fn id(node: &NodeRef) -> Option<String> {
+    attr(node, "id").filter(|id| IDENTIFIER.is_match(id) && !HAS_DIGITS.is_match(id))
+}
+
+/// Up to two classes usable in a selector, classes with digits are left out as they differ between pages
+fn classes(node: &NodeRef) -> Vec<String> {
+    attr(node, "class")
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter(|class| IDENTIFIER.is_match(class) && !HAS_DIGITS.is_match(class))
+        .take(2)
+        .map(String::from)
+        .collect()
+}
+
+fn tag(node: &NodeRef) -> String {
+    node.as_element()
+        .map(|element| element.name.local.to_string())
+        .unwrap_or_default()
+}
+
+fn attr(node: &NodeRef, name: &str) -> Option<String> {
+    let element = node.as_element()?;
+    let attributes = element.attributes.borrow();
+    attributes.get(name).map(String::from)
+}
+
+fn next_element(node: &NodeRef) -> Option<NodeRef> {
+    std::iter::successors(node.next_sibling(), |sibling| sibling.next_sibling())
+        .find(|sibling| sibling.as_element().is_some())
+}
+
+fn select(scope: &NodeRef, selector: &str) -> Vec<NodeRef> {
+    scope
+        .select(selector)
+        .map(|found| found.map(|element| element.as_node().clone()).collect())
+        .unwrap_or_default()
+}
+
+fn body(doc: &NodeRef) -> NodeRef {
+    select(doc, "body").into_iter().next().unwrap_or_else(|| doc.clone())
+}
+
+/// Every element in the node, the node included, except those that never hold a field
+fn elements(node: &NodeRef) -> impl Iterator<Item = NodeRef> {
+    node.inclusive_descendants().filter(|node| {
+        node.as_element().is_some()
+            && !node
+                .ancestors()
+                .chain([node.clone()])
+                .any(|node| SKIPPED_TAGS.contains(&tag(&node).as_str()))
+    })
+}
+
+/// The innermost element with the text, headings first
+fn find_text(doc: &NodeRef, text: &str) -> Option<NodeRef> {
+    let text = normalize(text);
+    let found: Vec<_> = elements(&body(doc))
+        .filter(|node| normalize(&node.own_text()) == text || normalize(&node.text_contents()) == text)
+        .collect();
+    let innermost = found.iter().filter(|node| {
+        !found
+            .iter()
+            .any(|other| other != *node && other.ancestors().any(|ancestor| ancestor == **node))
+    });
+    innermost
+        .sorted_by_key(|node| !matches!(tag(node).as_str(), "h1" | "h2" | "h3"))
+        .next()
+        .cloned()
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().join(" ").to_lowercase()
+}
+
+fn is_date(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty()
+        && text.len() < 40
+        && (HAS_DIGITS.is_match(text) || text.to_lowercase().contains("ago"))
+        && (guess_date_format(text).is_some() || try_parse_date(text, &[]).is_some())
+}
+
+fn guess_date_format(text: &str) -> Option<&'static str> {
+    DATE_FORMATS
+        .into_iter()
+        .find(|format| NaiveDate::parse_from_str(text.trim(), format).is_ok())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{borrow::Cow, path::PathBuf};
+
+    use reqwest::Url;
+    use serde_json::json;
+
+    use super::{draft_config, SamplePage};
+    use crate::config::loader::{load_sources, ConfigSource};
+
+    #[test]
+    fn test_draft_config() {
+        let html = r#"<html><head><meta property="og:image" content="/banner.jpg"></head><body>
+            <div class="post-title"><h1>God of Martial Arts</h1></div>
+            <div class="summary_image"><img data-src="/cover.jpg" alt="God of Martial Arts"></div>
+            <div class="post-content_item"><div class="summary-heading"><h5>Alternative</h5></div>
+                <div class="summary-content alt-names">Martial God</div></div>
+            <div class="author-content"><a href="/manga-author/mad-snail/">Mad Snail</a></div>
+            <div class="artist-content"><a href="/manga-artist/mad-snail/">Mad Snail</a></div>
+            <div class="post-content_item"><div class="summary-heading"><h5>Type</h5></div>
+                <div class="summary-content type-name">Manhua</div></div>
+            <div class="genres-content"><a href="/manga-genre/action/">Action</a><a href="/manga-genre/drama/">Drama</a></div>
+            <div class="post-status"><div class="summary-content">OnGoing</div></div>
+            <div class="summary__content"><p>Lin Feng was a disciple of the Cloud Sect until he was betrayed.</p></div>
+            <ul class="main">
+                <li class="wp-manga-chapter"><a href="/manga/god-of-martial-arts/chapter-2/">Chapter 2</a>
+                    <span class="chapter-release-date"><i>September 10, 2021</i></span></li>
+                <li class="wp-manga-chapter"><a href="/manga/god-of-martial-arts/chapter-1/">Chapter 1</a>
+                    <span class="chapter-release-date"><i>September 3, 2021</i></span></li>
+            </ul>
+        </body></html>"#;
+        let chapter_html = std::fs::read_to_string("tests/fragments/madara_chapter.html").unwrap();
+        let url = Url::parse("https://www.isekaiscan.top/manga/god-of-martial-arts/").unwrap();
+        let draft = draft_config(&SamplePage {
+            url: &url,
+            html,
+            title: "God of Martial Arts",
+            chapter_title: Some("Chapter 2"),
+            chapter_html: Some(&chapter_html),
+            image_url: Some("https://isekaiscan.top/wp-content/uploads/WP-manga/data/manga_25960/chapter-3/04.jpg"),
+        })
+        .unwrap();
+
+        let config = &draft.config;
+        assert_eq!(config["name"], "isekaiscan");
+        assert_eq!(config["accept"]["hostnames"], json!(["www.isekaiscan.top"]));
+        assert_eq!(config["accept"]["themes"], json!(["madara"]));
+        let manga = &config["manga"];
+        assert_eq!(manga["title"], "div.post-title h1");
+        assert_eq!(manga["description"], "div.summary__content");
+        assert_eq!(manga["cover_url"]["selector"], "div.summary_image img");
+        assert_eq!(manga["status"], "div.post-status div.summary-content");
+        assert_eq!(manga["authors"], "div.author-content a");
+        assert_eq!(manga["artists"], "div.artist-content a");
+        assert_eq!(manga["publication_type"], "div.summary-content.type-name");
+        assert_eq!(manga["genres"], "div.genres-content a");
+        assert_eq!(manga["alt_titles"], "div.summary-content.alt-names");
+        assert_eq!(manga["chapter"]["base"], "li.wp-manga-chapter");
+        assert_eq!(manga["chapter"]["title"], "a");
+        assert_eq!(manga["chapter"]["date"], "span.chapter-release-date i");
+        assert_eq!(config["date_formats"], json!(["%B %d, %Y"]));
+        let image_selector = &config["images"]["image_selector"];
+        assert_eq!(image_selector["selector"], "img.wp-manga-chapter-img");
+        assert_eq!(
+            image_selector["options"]["text_selection"]["attributes"],
+            json!(["data-src", "src"])
+        );
+
+        assert!(draft.issues().is_empty(), "{:?}", draft.issues());
+        let configs = load_sources(&[ConfigSource::Text {
+            name: PathBuf::from(draft.file_name()),
+            text: Cow::Owned(draft.to_yaml()),
+        }])
+        .unwrap();
+        assert_eq!(configs[0].name, "isekaiscan");
+    }
+}
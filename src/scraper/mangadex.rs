@@ -1,34 +1,155 @@
+use std::future::Future;
 use std::time::Duration;
 use std::vec;
 
 use chrono::DateTime;
 use mangadex_api::v5::schema::RelatedAttributes;
 use mangadex_api::v5::MangaDexClient;
-use mangadex_api_schema_rust::v5::ChapterObject;
+use mangadex_api_schema_rust::v5::{ChapterObject, MangaObject};
+use mangadex_api_types_rust::error::Error as MangaDexError;
 use mangadex_api_types_rust::{
-    ChapterSortOrder, IncludeFuturePublishAt, IncludeFutureUpdates, Language, MangaStatus, OrderDirection,
-    ReferenceExpansionResource, RelationshipType,
+    ChapterSortOrder, ContentRating as MangaDexContentRating, IncludeFuturePublishAt, IncludeFutureUpdates, Language,
+    MangaStatus as MangaDexStatus, OrderDirection, ReferenceExpansionResource, RelationshipType,
 };
 use reqwest::Url;
-use tokio::time::sleep;
 
+use crate::config::http::RateLimit;
 use crate::error::ScrapeError;
 use crate::model::*;
+use crate::rate_limit::RATE_LIMITER;
 
 use super::MangaScraper;
 
+/// Number of search results per page
+const SEARCH_PAGE_SIZE: u32 = 20;
+/// Host of the API, which allows about 5 requests per second
+const API_HOST: &str = "api.mangadex.org";
+/// Requests made before a rate limited API request is given up on
+const MAX_ATTEMPTS: u32 = 3;
+/// How long to hold off the API after it said there were too many requests, times the attempt
+const RATE_LIMITED_WAIT: Duration = Duration::from_secs(2);
+
+/// Which version of the chapter images MangaDex serves
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum ImageQuality {
+    /// The original images
+    Data,
+    /// Compressed images, which are smaller and load faster
+    #[default]
+    DataSaver,
+}
+
+impl ImageQuality {
+    fn path(self) -> &'static str {
+        match self {
+            ImageQuality::Data => "data",
+            ImageQuality::DataSaver => "data-saver",
+        }
+    }
+}
+
 pub struct MangaDex {
     client: MangaDexClient,
+    image_quality: ImageQuality,
 }
 
 impl MangaDex {
     pub fn new() -> Self {
+        Self::new_with_image_quality(ImageQuality::default())
+    }
+
+    pub fn new_with_image_quality(image_quality: ImageQuality) -> Self {
+        RATE_LIMITER.configure(
+            API_HOST,
+            &RateLimit {
+                requests_per_second: 5.0,
+                burst: 5,
+                max_in_flight: 5,
+            },
+        );
         MangaDex {
             client: MangaDexClient::default(),
+            image_quality,
         }
     }
 }
 
+fn status(status: MangaDexStatus) -> MangaStatus {
+    match status {
+        MangaDexStatus::Ongoing => MangaStatus::Ongoing,
+        MangaDexStatus::Completed => MangaStatus::Completed,
+        MangaDexStatus::Hiatus => MangaStatus::Hiatus,
+        MangaDexStatus::Cancelled => MangaStatus::Cancelled,
+        _ => MangaStatus::Unknown,
+    }
+}
+
+fn content_rating(content_rating: Option<&MangaDexContentRating>) -> ContentRating {
+    match content_rating {
+        Some(MangaDexContentRating::Safe) => ContentRating::Safe,
+        Some(MangaDexContentRating::Suggestive) => ContentRating::Suggestive,
+        Some(MangaDexContentRating::Erotica) => ContentRating::Erotica,
+        Some(MangaDexContentRating::Pornographic) => ContentRating::Pornographic,
+        _ => ContentRating::Unknown,
+    }
+}
+
+/// Names of the people of the relationship type, which have to be included in the response
+fn people(manga: &MangaObject, relationship_type: RelationshipType) -> Vec<String> {
+    manga
+        .relationships
+        .iter()
+        .filter(|a| a.type_ == relationship_type)
+        .filter_map(|a| {
+            if let Some(RelatedAttributes::Author(author)) = &a.attributes {
+                Some(author.name.to_owned())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Send an API request once the rate limiter allows it, again after a while when the API says it was too many
+///
+/// The client does not go through [`crate::rate_limit::RateLimitMiddleware`] and its errors do not carry the
+/// response headers, so there is no `Retry-After` to honour and the host is held off for a growing while instead
+async fn throttled<T, F: Future<Output = Result<T, ScrapeError>>>(request: impl Fn() -> F) -> Result<T, ScrapeError> {
+    let mut attempt = 1;
+    loop {
+        let result = {
+            let _permit = RATE_LIMITER.acquire(API_HOST).await;
+            request().await
+        };
+        match result {
+            Err(e) if attempt < MAX_ATTEMPTS && is_rate_limited(&e) => {
+                let wait = RATE_LIMITED_WAIT * attempt;
+                warn!("[rate limit] {API_HOST} said there were too many requests, waiting {}s", wait.as_secs());
+                RATE_LIMITER.retry_after(API_HOST, wait);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether the API refused a request with `429 Too Many Requests`, which the client reports as `RateLimitExcedeed`
+fn is_rate_limited(error: &ScrapeError) -> bool {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(error);
+    while let Some(error) = source {
+        if matches!(error.downcast_ref::<MangaDexError>(), Some(MangaDexError::RateLimitExcedeed))
+            || error
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(|e| e.status() == Some(reqwest::StatusCode::TOO_MANY_REQUESTS))
+        {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
 #[async_trait::async_trait]
 impl MangaScraper for MangaDex {
     async fn manga(&self, url: &Url) -> Result<Manga, ScrapeError> {
@@ -47,22 +168,25 @@ impl MangaScraper for MangaDex {
         )))?)
         .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?;
 
-        let manga = self
-            .client
-            .manga()
-            .get()
-            .add_manga_id(uuid)
-            .include(&ReferenceExpansionResource::Author)
-            .include(&ReferenceExpansionResource::Chapter)
-            .build()
-            .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
-            .send()
-            .await
-            .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
-            .data
-            .first()
-            .ok_or(ScrapeError::UnknownErrorStr("Manga not found"))?
-            .clone();
+        let manga = throttled(|| async move {
+            self.client
+                .manga()
+                .get()
+                .add_manga_id(uuid)
+                .include(&ReferenceExpansionResource::Author)
+                .include(&ReferenceExpansionResource::Artist)
+                .include(&ReferenceExpansionResource::Chapter)
+                .build()
+                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
+                .send()
+                .await
+                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))
+        })
+        .await?
+        .data
+        .first()
+        .ok_or(ScrapeError::UnknownErrorStr("Manga not found"))?
+        .clone();
 
         let cover_id = manga
             .relationships
@@ -70,16 +194,18 @@ impl MangaScraper for MangaDex {
             .find(|related| related.type_ == RelationshipType::CoverArt);
 
         let cover = if let Some(relationship) = cover_id {
-            let cover = self
-                .client
-                .cover()
-                .get()
-                .add_cover_id(&relationship.id)
-                .build()
-                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
-                .send()
-                .await
-                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?;
+            let cover = throttled(|| async move {
+                self.client
+                    .cover()
+                    .get()
+                    .add_cover_id(&relationship.id)
+                    .build()
+                    .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
+                    .send()
+                    .await
+                    .map_err(|e| ScrapeError::UnknownError(Box::new(e)))
+            })
+            .await?;
 
             Some(
                 Url::parse(&format!(
@@ -104,24 +230,22 @@ impl MangaScraper for MangaDex {
         let mut total: u32 = 0;
 
         while offset == 0 || offset < total {
-            if offset != 0 && offset % 400 == 0 {
-                // When 3 requests are made, wait one second before making the next
-                sleep(Duration::from_secs(1)).await;
-            }
-            let results = self
-                .client
-                .chapter()
-                .get()
-                .manga_id(*uuid)
-                .limit(100u32)
-                .offset(offset)
-                .include_future_publish_at(IncludeFuturePublishAt::Exclude)
-                .include_future_updates(IncludeFutureUpdates::Exclude)
-                .add_translated_language(Language::English)
-                .order(ChapterSortOrder::Chapter(OrderDirection::Descending))
-                .send()
-                .await
-                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?;
+            let results = throttled(|| async move {
+                self.client
+                    .chapter()
+                    .get()
+                    .manga_id(*uuid)
+                    .limit(100u32)
+                    .offset(offset)
+                    .include_future_publish_at(IncludeFuturePublishAt::Exclude)
+                    .include_future_updates(IncludeFutureUpdates::Exclude)
+                    .add_translated_language(Language::English)
+                    .order(ChapterSortOrder::Chapter(OrderDirection::Descending))
+                    .send()
+                    .await
+                    .map_err(|e| ScrapeError::UnknownError(Box::new(e)))
+            })
+            .await?;
 
             chapters.append(&mut results.data.clone());
             total = results.total;
@@ -141,6 +265,7 @@ impl MangaScraper for MangaDex {
                     .unwrap();
                 Chapter {
                     number,
+                    volume: chapter.attributes.volume.as_ref().and_then(|volume| volume.parse().ok()),
                     date: Some(
                         DateTime::from_timestamp_secs(chapter.attributes.created_at.as_ref().unix_timestamp()).unwrap(),
                     ),
@@ -155,6 +280,17 @@ impl MangaScraper for MangaDex {
             })
             .collect();
 
+        // The ISO 639-1 code the language serializes to, like ja or zh-hk
+        let original_language = serde_json::to_value(&manga.attributes.original_language)
+            .ok()
+            .and_then(|language| language.as_str().map(String::from));
+        let sources = MangaSources {
+            title: Some(MetadataSource::Api),
+            description: Some(MetadataSource::Api),
+            cover_url: cover.as_ref().map(|_| MetadataSource::Api),
+            authors: Some(MetadataSource::Api),
+            genres: Some(MetadataSource::Api),
+        };
         Ok(Manga {
             url: url.clone(),
             cover_url: cover,
@@ -177,18 +313,8 @@ impl MangaScraper for MangaDex {
                 .iter()
                 .flat_map(|a| a.values().map(|a| a.to_owned()).collect::<Vec<String>>())
                 .collect(),
-            authors: manga
-                .relationships
-                .iter()
-                .filter(|a| a.type_ == RelationshipType::Author)
-                .filter_map(|a| {
-                    if let Some(RelatedAttributes::Author(author)) = &a.attributes {
-                        Some(author.name.to_owned())
-                    } else {
-                        None
-                    }
-                })
-                .collect(),
+            authors: people(&manga, RelationshipType::Author),
+            artists: people(&manga, RelationshipType::Artist),
             genres: manga
                 .attributes
                 .tags
@@ -198,9 +324,17 @@ impl MangaScraper for MangaDex {
                 .filter(|a| a.is_some())
                 .map(|a| a.unwrap().to_owned())
                 .collect(),
+            content_rating: content_rating(manga.attributes.content_rating.as_ref()),
+            publication_type: original_language
+                .as_deref()
+                .map_or(PublicationType::Unknown, PublicationType::from_language),
+            year: manga.attributes.year.map(u32::from),
+            original_language,
             chapters,
-            is_ongoing: manga.attributes.status == MangaStatus::Ongoing,
-            status: Some(format!("{:?}", manga.attributes.status)),
+            is_ongoing: status(manga.attributes.status).is_ongoing(),
+            status: status(manga.attributes.status),
+            source_id: Some(String::from("mangadex")),
+            sources,
         })
     }
 
@@ -220,28 +354,32 @@ impl MangaScraper for MangaDex {
         )))?)
         .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?;
 
-        let at_home = self
-            .client
-            .at_home()
-            .server()
-            .id(*uuid)
-            .get()
-            .build()
-            .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
-            .send()
-            .await
-            .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?;
-
-        let images: Vec<Url> = at_home
-            .chapter
-            .data_saver
+        let at_home = throttled(|| async move {
+            self.client
+                .at_home()
+                .server()
+                .id(*uuid)
+                .get()
+                .build()
+                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
+                .send()
+                .await
+                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))
+        })
+        .await?;
+
+        let filenames = match self.image_quality {
+            ImageQuality::Data => &at_home.chapter.data,
+            ImageQuality::DataSaver => &at_home.chapter.data_saver,
+        };
+        let images: Vec<Url> = filenames
             .iter()
             .map(|filename| {
                 at_home
                     .base_url
                     .join(&format!(
                         "/{quality_mode}/{chapter_hash}/{page_filename}",
-                        quality_mode = "data-saver",
+                        quality_mode = self.image_quality.path(),
                         chapter_hash = at_home.chapter.hash,
                         page_filename = filename
                     ))
@@ -252,20 +390,26 @@ impl MangaScraper for MangaDex {
         Ok(images)
     }
 
-    async fn search(&self, query: &str, _hostnames: &[String]) -> Result<Vec<SearchManga>, ScrapeError> {
-        let results = self
-            .client
-            .search()
-            .manga()
-            .add_available_translated_language(Language::English)
-            .title(query)
-            .include(ReferenceExpansionResource::CoverArt)
-            .build()
-            .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
-            .send()
-            .await
-            .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?;
+    async fn search_page(&self, query: &str, _hostnames: &[String], page: u32) -> Result<SearchPage, ScrapeError> {
+        let offset = page.saturating_sub(1) * SEARCH_PAGE_SIZE;
+        let results = throttled(|| async move {
+            self.client
+                .search()
+                .manga()
+                .add_available_translated_language(Language::English)
+                .title(query)
+                .include(ReferenceExpansionResource::CoverArt)
+                .limit(SEARCH_PAGE_SIZE)
+                .offset(offset)
+                .build()
+                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))?
+                .send()
+                .await
+                .map_err(|e| ScrapeError::UnknownError(Box::new(e)))
+        })
+        .await?;
 
+        let has_more = offset + (results.data.len() as u32) < results.total;
         let search_results = results
             .data
             .iter()
@@ -303,7 +447,11 @@ impl MangaScraper for MangaDex {
             })
             .collect();
 
-        Ok(search_results)
+        Ok(SearchPage {
+            page,
+            results: search_results,
+            has_more,
+        })
     }
 
     async fn accepts(&self, url: &Url) -> bool {
@@ -0,0 +1,199 @@
+use serde_json::Value;
+
+use crate::{config::string_selector_options::StringSelection, model::MetadataSource, util::json_path::json_to_text};
+
+use super::document::DocWrapper;
+
+/// schema.org types that describe a manga
+const JSON_LD_TYPES: [&str; 4] = ["Book", "BookSeries", "ComicSeries", "CreativeWorkSeries"];
+
+/// Standard metadata of a manga page, for the fields the selectors of a config do not find
+///
+/// A schema.org object in a JSON-LD script is preferred over the Open Graph meta tags, whose title and image
+/// are often made for sharing, like "Read Solo Leveling Online Free" and a banner
+pub(super) struct PageMetadata {
+    doc: DocWrapper,
+    json_ld: Option<Value>,
+}
+
+impl PageMetadata {
+    pub fn new(doc: &DocWrapper) -> Self {
+        let json_ld = match doc {
+            DocWrapper::Html(_) => doc
+                .select_texts(
+                    "script[type='application/ld+json']",
+                    &StringSelection::AllText {
+                        join_with: String::new(),
+                    },
+                )
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|script| serde_json::from_str::<Value>(script.trim()).ok())
+                .find_map(|json| find_manga_object(&json).cloned()),
+            DocWrapper::Json(_) => None,
+        };
+        Self {
+            doc: doc.clone(),
+            json_ld,
+        }
+    }
+
+    pub fn title(&self) -> Option<(String, MetadataSource)> {
+        self.json_ld_text(&["name", "headline"])
+            .map(|title| (title, MetadataSource::JsonLd))
+            .or_else(|| {
+                self.open_graph("og:title")
+                    .map(|title| (title, MetadataSource::OpenGraph))
+            })
+    }
+
+    pub fn description(&self) -> Option<(String, MetadataSource)> {
+        self.json_ld_text(&["description"])
+            .map(|description| (description, MetadataSource::JsonLd))
+            .or_else(|| {
+                self.open_graph("og:description")
+                    .map(|description| (description, MetadataSource::OpenGraph))
+            })
+    }
+
+    /// URL of the cover as written on the page, it may be relative
+    pub fn cover_url(&self) -> Option<(String, MetadataSource)> {
+        self.json_ld_text(&["image", "thumbnailUrl"])
+            .map(|cover_url| (cover_url, MetadataSource::JsonLd))
+            .or_else(|| {
+                self.open_graph("og:image")
+                    .map(|cover_url| (cover_url, MetadataSource::OpenGraph))
+            })
+    }
+
+    pub fn authors(&self) -> Option<(Vec<String>, MetadataSource)> {
+        self.json_ld_texts("author")
+            .map(|authors| (authors, MetadataSource::JsonLd))
+    }
+
+    pub fn genres(&self) -> Option<(Vec<String>, MetadataSource)> {
+        self.json_ld_texts("genre")
+            .map(|genres| (genres, MetadataSource::JsonLd))
+    }
+
+    fn open_graph(&self, property: &str) -> Option<String> {
+        let DocWrapper::Html(_) = self.doc else {
+            return None;
+        };
+        let content = self
+            .doc
+            .select_text(
+                &format!("meta[property='{property}'], meta[name='{property}']"),
+                &StringSelection::Attributes(vec![String::from("content")]),
+            )
+            .ok()?;
+        Some(content.trim().to_string()).filter(|content| !content.is_empty())
+    }
+
+    /// Text of the first of the keys the JSON-LD object has
+    fn json_ld_text(&self, keys: &[&str]) -> Option<String> {
+        let object = self.json_ld.as_ref()?;
+        keys.iter()
+            .filter_map(|key| object.get(key))
+            .flat_map(texts)
+            .find(|text| !text.is_empty())
+    }
+
+    fn json_ld_texts(&self, key: &str) -> Option<Vec<String>> {
+        let texts: Vec<String> = texts(self.json_ld.as_ref()?.get(key)?)
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .collect();
+        Some(texts).filter(|texts| !texts.is_empty())
+    }
+}
+
+/// The first object with a manga type, looking into arrays and `@graph`
+fn find_manga_object(json: &Value) -> Option<&Value> {
+    match json {
+        Value::Array(items) => items.iter().find_map(find_manga_object),
+        Value::Object(object) => {
+            let types = object.get("@type").map(texts).unwrap_or_default();
+            if types
+                .iter()
+                .any(|type_name| JSON_LD_TYPES.contains(&type_name.as_str()))
+            {
+                Some(json)
+            } else {
+                object.get("@graph").and_then(find_manga_object)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Texts of a JSON-LD value, which is a text, a thing with a name or URL, or a list of either
+fn texts(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(texts).collect(),
+        Value::Object(object) => ["name", "url", "contentUrl", "@value"]
+            .iter()
+            .find_map(|key| object.get(*key))
+            .map(texts)
+            .unwrap_or_default(),
+        value => vec![json_to_text(value).trim().to_string()],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PageMetadata;
+    use crate::{model::MetadataSource, scraper::document::DocWrapper};
+
+    #[test]
+    fn test_page_metadata() {
+        let doc = DocWrapper::from_html(
+            r#"<html><head>
+            <meta property="og:title" content="Read Solo Leveling Online">
+            <meta property="og:description" content="A hunter levels up.">
+            <meta property="og:image" content="/banner.jpg">
+            <script type="application/ld+json">
+                {"@context": "https://schema.org", "@graph": [
+                    {"@type": "WebSite", "name": "Example"},
+                    {"@type": ["ComicSeries"], "name": "Solo Leveling",
+                     "image": {"@type": "ImageObject", "url": "https://example.com/cover.jpg"},
+                     "author": [{"@type": "Person", "name": "Chugong"}, "DUBU"],
+                     "genre": ["Action", "Fantasy"]}
+                ]}
+            </script>
+            </head><body></body></html>"#,
+        )
+        .unwrap();
+        let metadata = PageMetadata::new(&doc);
+
+        assert_eq!(
+            metadata.title(),
+            Some((String::from("Solo Leveling"), MetadataSource::JsonLd))
+        );
+        assert_eq!(
+            metadata.description(),
+            Some((String::from("A hunter levels up."), MetadataSource::OpenGraph))
+        );
+        assert_eq!(
+            metadata.cover_url(),
+            Some((String::from("https://example.com/cover.jpg"), MetadataSource::JsonLd))
+        );
+        assert_eq!(
+            metadata.authors(),
+            Some((
+                vec![String::from("Chugong"), String::from("DUBU")],
+                MetadataSource::JsonLd
+            ))
+        );
+        assert_eq!(metadata.genres().unwrap().0, vec!["Action", "Fantasy"]);
+
+        let doc =
+            DocWrapper::from_html(r#"<html><head><meta name="og:title" content="Only OG"></head></html>"#).unwrap();
+        let metadata = PageMetadata::new(&doc);
+        assert_eq!(
+            metadata.title(),
+            Some((String::from("Only OG"), MetadataSource::OpenGraph))
+        );
+        assert_eq!(metadata.authors(), None);
+    }
+}
@@ -1,10 +1,15 @@
 use crate::{
     error::ScrapeError,
-    model::{Manga, SearchManga},
+    model::{Manga, SearchManga, SearchPage},
 };
 use reqwest::Url;
 
+mod document;
+pub mod fingerprint;
+mod metadata;
+pub mod fixtures;
 pub mod generic;
+pub mod infer;
 pub mod mangadex;
 pub mod scraper_manager;
 
@@ -14,7 +19,12 @@ pub trait MangaScraper: Send + Sync {
     async fn manga(&self, url: &Url) -> Result<Manga, ScrapeError>;
     async fn chapter_images(&self, chapter_url: &Url) -> Result<Vec<Url>, ScrapeError>;
 
-    async fn search(&self, query: &str, hostnames: &[String]) -> Result<Vec<SearchManga>, ScrapeError>;
+    /// First page of search results
+    async fn search(&self, query: &str, hostnames: &[String]) -> Result<Vec<SearchManga>, ScrapeError> {
+        Ok(self.search_page(query, hostnames, 1).await?.results)
+    }
+    /// Page of search results, starting at 1
+    async fn search_page(&self, query: &str, hostnames: &[String], page: u32) -> Result<SearchPage, ScrapeError>;
     fn search_accepts(&self, hostname: &str) -> bool;
     fn searchable_hostnames(&self) -> Vec<String>;
 }
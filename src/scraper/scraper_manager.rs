@@ -2,7 +2,7 @@ use reqwest::Url;
 
 use crate::{
     error::ScrapeError,
-    model::{Manga, SearchManga},
+    model::{Manga, SearchPage},
     scraper::{generic::GenericScraper, mangadex::MangaDex, MangaScraper},
 };
 
@@ -14,6 +14,10 @@ impl ScraperManager {
     pub fn new() -> Self {
         Self::default()
     }
+
+    pub fn new_with_scrapers(scrapers: Vec<Box<dyn MangaScraper>>) -> Self {
+        Self { scrapers }
+    }
 }
 
 impl Default for ScraperManager {
@@ -69,19 +73,23 @@ impl MangaScraper for ScraperManager {
         true
     }
 
-    async fn search(
+    async fn search_page(
         &self,
         query: &str,
         hostnames: &[String],
-    ) -> Result<Vec<SearchManga>, ScrapeError> {
+        page: u32,
+    ) -> Result<SearchPage, ScrapeError> {
         let mut err = None;
-        let mut search_results = vec![];
+        let mut search_results = SearchPage::empty(page);
         for hostname in hostnames {
             for scraper in self.scrapers.iter() {
                 if scraper.search_accepts(&hostname) {
-                    let result = scraper.search(query, &[hostname.to_string()]).await;
+                    let result = scraper.search_page(query, &[hostname.to_string()], page).await;
                     match result {
-                        Ok(mut results) => search_results.append(&mut results),
+                        Ok(mut results) => {
+                            search_results.results.append(&mut results.results);
+                            search_results.has_more |= results.has_more;
+                        }
                         Err(e) => {
                             if !matches!(e, ScrapeError::SearchNotSupported(_)) {
                                 error!("Error parsing search: {:?}", e);
@@ -93,7 +101,7 @@ impl MangaScraper for ScraperManager {
             }
         }
 
-        if !search_results.is_empty() || err.is_none() {
+        if !search_results.results.is_empty() || err.is_none() {
             return Ok(search_results);
         }
 
@@ -0,0 +1,185 @@
+use serde_json::Value;
+
+/// A small JSONPath subset used to select values from JSON responses
+///
+/// Supported syntax:
+/// - `$` the current value (optional at the start)
+/// - `.key` or `['key']` an object key
+/// - `[0]`, `[-1]` an array index, negative counts from the end
+/// - `.*` or `[*]` every item of an array or every value of an object
+/// - `..key` every `key` at any depth
+///
+/// eg. `$.data.chapters[*].title`
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    Descendant(String),
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let path = path.trim();
+        let mut rest = path.strip_prefix('$').unwrap_or(path);
+        let mut segments = vec![];
+
+        // Allow "data.title" as a shorthand for "$.data.title"
+        if !rest.is_empty() && !rest.starts_with(['.', '[']) {
+            let (key, remaining) = split_key(rest);
+            segments.push(Segment::Key(key.to_string()));
+            rest = remaining;
+        }
+
+        while !rest.is_empty() {
+            if let Some(remaining) = rest.strip_prefix("..") {
+                let (key, remaining) = split_key(remaining);
+                if key.is_empty() {
+                    return Err(format!("Missing key after '..' in {path}"));
+                }
+                segments.push(Segment::Descendant(key.to_string()));
+                rest = remaining;
+            } else if let Some(remaining) = rest.strip_prefix('.') {
+                let (key, remaining) = split_key(remaining);
+                segments.push(match key {
+                    "" => return Err(format!("Missing key after '.' in {path}")),
+                    "*" => Segment::Wildcard,
+                    key => Segment::Key(key.to_string()),
+                });
+                rest = remaining;
+            } else if let Some(remaining) = rest.strip_prefix('[') {
+                let end = remaining
+                    .find(']')
+                    .ok_or_else(|| format!("Missing ']' in {path}"))?;
+                let inner = remaining[..end].trim();
+                segments.push(if inner == "*" {
+                    Segment::Wildcard
+                } else if let Some(key) = inner
+                    .strip_prefix('\'')
+                    .and_then(|key| key.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|key| key.strip_suffix('"')))
+                {
+                    Segment::Key(key.to_string())
+                } else {
+                    Segment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| format!("Invalid index '{inner}' in {path}"))?,
+                    )
+                });
+                rest = &remaining[end + 1..];
+            } else {
+                return Err(format!("Unexpected '{rest}' in {path}"));
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Every value matching this path
+    pub fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        let mut current = vec![value];
+        for segment in &self.segments {
+            let mut next = vec![];
+            for value in current {
+                match segment {
+                    Segment::Key(key) => next.extend(value.get(key)),
+                    Segment::Index(index) => {
+                        if let Value::Array(array) = value {
+                            let index = if *index < 0 {
+                                array.len() as i64 + index
+                            } else {
+                                *index
+                            };
+                            next.extend(usize::try_from(index).ok().and_then(|index| array.get(index)));
+                        }
+                    }
+                    Segment::Wildcard => match value {
+                        Value::Array(array) => next.extend(array),
+                        Value::Object(object) => next.extend(object.values()),
+                        _ => {}
+                    },
+                    Segment::Descendant(key) => collect_descendants(value, key, &mut next),
+                }
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+fn split_key(path: &str) -> (&str, &str) {
+    let end = path.find(['.', '[']).unwrap_or(path.len());
+    (&path[..end], &path[end..])
+}
+
+fn collect_descendants<'a>(value: &'a Value, key: &str, found: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(value) = object.get(key) {
+                found.push(value);
+            }
+            for value in object.values() {
+                collect_descendants(value, key, found);
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                collect_descendants(value, key, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Text representation of a JSON value, strings are returned without quotes
+pub fn json_to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::JsonPath;
+
+    #[test]
+    fn json_path_select() {
+        let value = json!({
+            "data": {
+                "title": "Solo Leveling",
+                "chapters": [
+                    { "title": "Chapter 1", "url": "/c/1" },
+                    { "title": "Chapter 2", "url": "/c/2", "extra": { "title": "Bonus" } }
+                ]
+            }
+        });
+
+        let select = |path: &str| JsonPath::parse(path).unwrap().select(&value).into_iter().cloned().collect::<Vec<_>>();
+
+        assert_eq!(select("$.data.title"), vec![json!("Solo Leveling")]);
+        assert_eq!(select("data.title"), vec![json!("Solo Leveling")]);
+        assert_eq!(select("$['data']['title']"), vec![json!("Solo Leveling")]);
+        assert_eq!(select("$.data.chapters[*].url"), vec![json!("/c/1"), json!("/c/2")]);
+        assert_eq!(select("$.data.chapters[-1].url"), vec![json!("/c/2")]);
+        assert_eq!(select("$.data.chapters[5]"), Vec::<serde_json::Value>::new());
+        assert_eq!(select("$.data.chapters[1].extra.*"), vec![json!("Bonus")]);
+        assert_eq!(select("$..title").len(), 4);
+        assert_eq!(select("$"), vec![value.clone()]);
+
+        assert!(JsonPath::parse("$.data[").is_err());
+        assert!(JsonPath::parse("$.data[x]").is_err());
+        assert!(JsonPath::parse("$.").is_err());
+    }
+}
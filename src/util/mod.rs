@@ -1,3 +1,5 @@
 pub mod date;
+pub mod json_path;
 pub mod kuchiki_elements;
 pub mod number;
+pub mod script;
@@ -2,6 +2,14 @@ use regex::Regex;
 
 lazy_static::lazy_static! {
     static ref DECIMAL: Regex = Regex::new(r"(?<decimal>\d+(\.\d+)?)").unwrap();
+    static ref VOLUME: Regex = Regex::new(r"(?i)\b(?:vol(?:ume)?|tome)\.?\s*(?<volume>\d+(?:\.\d+)?)").unwrap();
+    static ref CHAPTER: Regex =
+        Regex::new(r"(?i)\b(?:ch(?:ap(?:ter)?)?|ep(?:isode)?)\.?\s*(?<number>\d+(?:\.\d+)?)").unwrap();
+    static ref PROLOGUE: Regex = Regex::new(r"(?i)\bprologue\b").unwrap();
+    static ref SPECIAL: Regex = Regex::new(
+        r"(?i)\b(?:extras?|side\s*stor(?:y|ies)|specials?|bonus|omake|epilogue|afterword)\b(?:\W*(?<number>\d{1,2})\b)?"
+    )
+    .unwrap();
 }
 
 pub fn try_parse_number(number: &str) -> Option<f32> {
@@ -14,3 +22,103 @@ pub fn try_parse_number(number: &str) -> Option<f32> {
 
     None
 }
+
+/// Where a chapter goes in the list of chapters
+#[cfg_attr(feature = "debug", derive(Debug))]
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChapterNumber {
+    Number(f32),
+    /// An extra, side story or the like, which goes right after the chapter before it,
+    /// with its own number when it has one ("Side Story 3")
+    Special(Option<u32>),
+    /// Nothing that looks like a number, numbered after the chapter before it like a special
+    Unknown,
+}
+
+/// Chapter number and volume of a chapter title like "Vol.2 Chapter 15.5" or "Ch. 10-11"
+///
+/// A prologue is chapter 0, a range is numbered by its first chapter
+pub fn parse_chapter_number(text: &str) -> (ChapterNumber, Option<f32>) {
+    let volume = VOLUME
+        .captures(text)
+        .and_then(|captures| captures["volume"].parse().ok());
+
+    let number = if let Some(captures) = CHAPTER.captures(text) {
+        captures["number"]
+            .parse()
+            .map_or(ChapterNumber::Unknown, ChapterNumber::Number)
+    } else if PROLOGUE.is_match(text) {
+        ChapterNumber::Number(0.0)
+    } else if let Some(captures) = SPECIAL.captures(text) {
+        ChapterNumber::Special(captures.name("number").and_then(|number| number.as_str().parse().ok()))
+    } else {
+        // Any other number that is not the volume, like "15" or "Vol.2 - 15"
+        try_parse_number(&VOLUME.replace(text, ""))
+            .map_or(ChapterNumber::Unknown, ChapterNumber::Number)
+    };
+
+    (number, volume)
+}
+
+/// Chapter numbers of a list of chapters, newest first
+///
+/// Specials and chapters without a number get the number of the chapter before them plus 0.01 for every one of them
+/// since that chapter, a special with its own number uses that instead ("Side Story 3" after chapter 50 is 50.03)
+pub fn order_chapters(numbers: &[ChapterNumber]) -> Vec<f32> {
+    let mut ordered = vec![0.0; numbers.len()];
+    let mut previous = 0.0;
+    let mut extras = 0;
+    for (index, number) in numbers.iter().enumerate().rev() {
+        ordered[index] = match number {
+            ChapterNumber::Number(number) => {
+                extras = 0;
+                previous = *number;
+                *number
+            }
+            ChapterNumber::Special(Some(special)) => {
+                extras = extras.max(*special);
+                previous + 0.01 * *special as f32
+            }
+            ChapterNumber::Special(None) | ChapterNumber::Unknown => {
+                extras += 1;
+                previous + 0.01 * extras as f32
+            }
+        };
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod test {
+    use super::{order_chapters, parse_chapter_number, ChapterNumber};
+
+    #[test]
+    fn test_parse_chapter_number() {
+        assert_eq!(
+            parse_chapter_number("Vol.2 Chapter 15.5"),
+            (ChapterNumber::Number(15.5), Some(2.0))
+        );
+        assert_eq!(parse_chapter_number("Ch. 10-11"), (ChapterNumber::Number(10.0), None));
+        assert_eq!(parse_chapter_number("Episode 7: The Gate"), (ChapterNumber::Number(7.0), None));
+        assert_eq!(parse_chapter_number("Volume 3 - 21"), (ChapterNumber::Number(21.0), Some(3.0)));
+        assert_eq!(parse_chapter_number("Prologue"), (ChapterNumber::Number(0.0), None));
+        assert_eq!(parse_chapter_number("Side Story 3"), (ChapterNumber::Special(Some(3)), None));
+        assert_eq!(parse_chapter_number("Vol.4 Extra"), (ChapterNumber::Special(None), Some(4.0)));
+        assert_eq!(parse_chapter_number("Oneshot"), (ChapterNumber::Unknown, None));
+    }
+
+    #[test]
+    fn test_order_chapters() {
+        let numbers = [
+            ChapterNumber::Special(None),
+            ChapterNumber::Special(None),
+            ChapterNumber::Number(2.0),
+            ChapterNumber::Unknown,
+            ChapterNumber::Number(1.0),
+            ChapterNumber::Special(Some(2)),
+            ChapterNumber::Special(None),
+            ChapterNumber::Number(0.0),
+        ];
+        assert_eq!(order_chapters(&numbers), vec![2.02, 2.01, 2.0, 1.01, 1.0, 0.02, 0.01, 0.0]);
+    }
+}
@@ -0,0 +1,144 @@
+use regex::Regex;
+use serde_json::Value;
+
+/// Find the literal assigned to a variable in a script
+///
+/// Matches `var name = ...`, `window.name = ...`, `name: ...` and `"name": ...`
+/// and returns the full literal (object, array, string or scalar) that follows.
+/// Comparisons like `name == x` and values that do not parse are skipped for a later assignment
+pub fn extract_variable<'a>(script: &'a str, name: &str) -> Option<&'a str> {
+    let assignment = Regex::new(&format!(r#"(?:^|[^\w$])["']?{}["']?\s*[=:]\s*"#, regex::escape(name))).ok()?;
+    for found in assignment.find_iter(script) {
+        let rest = &script[found.end()..];
+        if rest.starts_with('=') {
+            continue;
+        }
+        if let Some(literal) = extract_literal(rest).filter(|literal| parse_literal(literal).is_some()) {
+            return Some(literal);
+        }
+    }
+    None
+}
+
+/// The literal at the start of the text, brackets and quotes are balanced
+///
+/// `JSON.parse("...")` is unwrapped to the string it parses
+pub fn extract_literal(text: &str) -> Option<&str> {
+    let text = text.trim_start();
+    if let Some(inner) = text.strip_prefix("JSON.parse(") {
+        return extract_literal(inner);
+    }
+
+    let mut chars = text.char_indices();
+    let (_, first) = chars.next()?;
+    match first {
+        '{' | '[' => {
+            let mut depth = 0;
+            let mut quote = None;
+            let mut escaped = false;
+            for (index, char) in text.char_indices() {
+                if let Some(quote_char) = quote {
+                    if escaped {
+                        escaped = false;
+                    } else if char == '\\' {
+                        escaped = true;
+                    } else if char == quote_char {
+                        quote = None;
+                    }
+                    continue;
+                }
+                match char {
+                    '"' | '\'' | '`' => quote = Some(char),
+                    '{' | '[' => depth += 1,
+                    '}' | ']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(&text[..=index]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None
+        }
+        '"' | '\'' | '`' => {
+            let mut escaped = false;
+            for (index, char) in chars {
+                if escaped {
+                    escaped = false;
+                } else if char == '\\' {
+                    escaped = true;
+                } else if char == first {
+                    return Some(&text[..=index]);
+                }
+            }
+            None
+        }
+        _ => {
+            let end = text.find([';', ',', '\n', ')', '}', ']']).unwrap_or(text.len());
+            Some(text[..end].trim()).filter(|literal| !literal.is_empty())
+        }
+    }
+}
+
+/// Parse a JSON or JavaScript literal
+///
+/// String literals that contain JSON themselves (as with `JSON.parse`) are parsed again
+pub fn parse_literal(literal: &str) -> Option<Value> {
+    let literal = literal.trim();
+    // Template literals are plain strings when they have no substitutions
+    let literal = if literal.starts_with('`') && literal.ends_with('`') && literal.len() > 1 {
+        std::borrow::Cow::Owned(format!("\"{}\"", &literal[1..literal.len() - 1].replace('"', "\\\"")))
+    } else {
+        std::borrow::Cow::Borrowed(literal)
+    };
+
+    let value = serde_json::from_str::<Value>(&literal)
+        .ok()
+        .or_else(|| json5::from_str::<Value>(&literal).ok())?;
+
+    if let Value::String(string) = &value {
+        let trimmed = string.trim();
+        if trimmed.starts_with(['{', '[']) {
+            if let Some(inner) = parse_literal(trimmed) {
+                return Some(inner);
+            }
+        }
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{extract_variable, parse_literal};
+
+    #[test]
+    fn test_extract_variable() {
+        let script = r#"
+            var chapter_id = 1234;
+            var chapter_images = ["https://cdn.example.com/1.jpg", "https://cdn.example.com/2.jpg"];
+            window.__DATA__ = JSON.parse('{"title": "Solo Leveling", "tags": ["action"]}');
+            const config = { name: 'reader', pages: [1, 2,], nested: { "a]": "}" } };
+        "#;
+
+        let literal = extract_variable(script, "chapter_images").unwrap();
+        assert_eq!(
+            parse_literal(literal),
+            Some(json!(["https://cdn.example.com/1.jpg", "https://cdn.example.com/2.jpg"]))
+        );
+        assert_eq!(parse_literal(extract_variable(script, "chapter_id").unwrap()), Some(json!(1234)));
+        assert_eq!(
+            parse_literal(extract_variable(script, "__DATA__").unwrap()),
+            Some(json!({"title": "Solo Leveling", "tags": ["action"]}))
+        );
+        let config = parse_literal(extract_variable(script, "config").unwrap()).unwrap();
+        assert_eq!(config["name"], json!("reader"));
+        assert_eq!(config["nested"]["a]"], json!("}"));
+        assert_eq!(extract_variable(script, "missing"), None);
+
+        let script = "if (pages == undefined) { pages = [1, 2]; }";
+        assert_eq!(extract_variable(script, "pages"), Some("[1, 2]"));
+    }
+}
@@ -0,0 +1,10 @@
+use itertools::Itertools;
+use manga_parser::scraper::generic::GenericScraper;
+
+/// The offline tests of every config in `configs/`
+#[test_log::test(tokio::test)]
+async fn config_tests() {
+    let scraper = GenericScraper::new().unwrap();
+    let failures = scraper.run_config_tests().await;
+    assert!(failures.is_empty(), "{}", failures.iter().join("\n"));
+}